@@ -0,0 +1,86 @@
+//! Throughput of single-byte XOR brute forcing over every line of `challenge4.txt`,
+//! spawning a thread per candidate key versus running on a bounded [WorkerPool].
+//!
+//! Run with `cargo bench`.
+
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use zed_cryptopals_challenge::codec::adapter::CodecAPI;
+use zed_cryptopals_challenge::codec::hex::Hexadecimal;
+use zed_cryptopals_challenge::crack::pool::WorkerPool;
+use zed_cryptopals_challenge::crack::score::{ChiSquaredScorer, Scorer};
+use zed_cryptopals_challenge::crack::xor::{brute_top_n_in, repeating_key_xor};
+use zed_cryptopals_challenge::utils::fs::read_file;
+
+const ROUNDS: u32 = 5;
+
+/// The previous approach: 256 threads per line, one per candidate key.
+fn thread_per_key(lines: &[String]) -> Vec<u8> {
+    lines
+        .iter()
+        .map(|line| {
+            let crypt_text = Arc::new(Hexadecimal {}.to_plain(line.as_bytes()));
+            let queue = (0..=u8::MAX)
+                .map(|cipher| {
+                    let crypt_text = Arc::clone(&crypt_text);
+                    thread::spawn(move || {
+                        let plain = repeating_key_xor(&crypt_text, &[cipher]);
+                        (cipher, ChiSquaredScorer {}.score(&plain))
+                    })
+                })
+                .collect::<Vec<_>>();
+            queue
+                .into_iter()
+                .map(|q| q.join().unwrap())
+                .fold((0, f64::NEG_INFINITY), |a, b| if a.1 < b.1 { b } else { a })
+                .0
+        })
+        .collect()
+}
+
+/// One line per worker, each brute forced on its worker alone.
+fn worker_pool(lines: &[String]) -> Vec<u8> {
+    WorkerPool::default().map(lines, |line| {
+        let res = brute_top_n_in(
+            &WorkerPool::new(1),
+            &Hexadecimal {},
+            line.as_str(),
+            &ChiSquaredScorer {},
+            1,
+        );
+        res[0].get_key_byte().unwrap()
+    })
+}
+
+fn bench(name: &str, lines: &[String], f: fn(&[String]) -> Vec<u8>) -> Vec<u8> {
+    let mut res = Vec::new();
+    let mut elapsed = Duration::ZERO;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        res = f(lines);
+        elapsed += start.elapsed();
+    }
+    let per_round = elapsed / ROUNDS;
+    println!(
+        "{:<16} {:>10.2?} per round, {:>10.0} lines/s",
+        name,
+        per_round,
+        lines.len() as f64 / per_round.as_secs_f64()
+    );
+    res
+}
+
+fn main() {
+    let lines = read_file("_data/set1/challenge4.txt");
+    println!(
+        "{} lines, {} workers, {} rounds",
+        lines.len(),
+        WorkerPool::default().workers(),
+        ROUNDS
+    );
+
+    let spawned = bench("thread per key", &lines, thread_per_key);
+    let pooled = bench("worker pool", &lines, worker_pool);
+    assert_eq!(spawned, pooled, "both approaches must find the same keys");
+}
@@ -1,39 +1,46 @@
 //! Set 1/Challenge 4 <https://cryptopals.com/sets/1/challenges/4>
 
 use crate::codec::hex::Hexadecimal;
-use crate::crack::xor;
+use crate::crack::detect;
+use crate::crack::score::Scorer;
 
 /// One-off function to:
 /// 1. Read a file containing one hex per line.
-/// 1. Decrypt each line with xor_decrypt::brute.
-/// 1. Score the result with xor_decrypt::decrypt_score.
-/// 1. Return the decrypted string that scores the highest.
-pub fn find_encrypted_string(filename: &str) -> String {
-    let mut res: (String, usize) = (String::from(""), 0);
-    let codec = &Hexadecimal {};
+/// 1. Search the lines for the one encrypted with single-byte XOR, with [detect::single_byte_xor].
+/// 1. Return the decrypted string of the line whose decryption `scorer` rates the highest.
+///
+/// Returns an empty string if the file has no valid hex lines.
+pub fn find_encrypted_string<S: Scorer + Sync + ?Sized>(filename: &str, scorer: &S) -> String {
     let crypt_list = crate::utils::fs::read_file(filename);
 
-    for crypt_line in crypt_list {
-        let decrypted_result = xor::brute(codec, &crypt_line.as_str());
-        let line_decrypt = decrypted_result.get_decrypted_result();
-        let score = xor::ascii_score(line_decrypt.clone());
-        let line_decrypt_string = line_decrypt.iter().map(|c| *c as char).collect::<String>();
-        println!("decrypted: {}", line_decrypt_string);
-        if score > res.1 {
-            res = (line_decrypt_string, score);
-        }
-    }
-    res.0
+    detect::single_byte_xor(crypt_list, &Hexadecimal {}, scorer, 1)
+        .first()
+        .map(|found| {
+            found
+                .get_result()
+                .get_decrypted_result()
+                .iter()
+                .map(|c| *c as char)
+                .collect::<String>()
+        })
+        .unwrap_or_default()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::crack::score::{AsciiScorer, ChiSquaredScorer};
 
     #[test]
     fn test_find_encrypted_string() {
-        let res = find_encrypted_string("_data/set1/challenge4.txt");
         let expected = "Now that the party is jumping\n";
-        assert_eq!(res, expected);
+        assert_eq!(
+            find_encrypted_string("_data/set1/challenge4.txt", &AsciiScorer {}),
+            expected
+        );
+        assert_eq!(
+            find_encrypted_string("_data/set1/challenge4.txt", &ChiSquaredScorer {}),
+            expected
+        );
     }
 }
@@ -0,0 +1,296 @@
+//! AES, as specified in [FIPS-197](https://csrc.nist.gov/pubs/fips/197/final).
+//!
+//! A straightforward byte-oriented implementation, written for clarity over speed.
+//! It is not constant-time, so don't use it to protect anything real.
+
+use super::error::CipherError;
+use super::BlockCipher;
+
+/// AES works on 16 byte blocks, whatever the key size.
+pub const BLOCK_SIZE: usize = 16;
+
+/// Multiply `a` by `b` in GF(2^8), modulo the AES polynomial x^8 + x^4 + x^3 + x + 1.
+const fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut res = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            res ^= a;
+        }
+        let carry = a & 0x80 != 0;
+        a <<= 1;
+        if carry {
+            a ^= 0x1b;
+        }
+        b >>= 1;
+    }
+    res
+}
+
+/// Multiplicative inverse of `a` in GF(2^8), which is `a^254`. `0` maps to `0`.
+const fn gf_inv(a: u8) -> u8 {
+    let mut res = 1;
+    let mut i = 0;
+    while i < 254 {
+        res = gf_mul(res, a);
+        i += 1;
+    }
+    res
+}
+
+/// The substitution box of FIPS-197 §5.1.1:
+/// the multiplicative inverse, followed by an affine transformation.
+const fn sbox() -> [u8; 256] {
+    let mut sbox = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let b = gf_inv(i as u8);
+        sbox[i] =
+            b ^ b.rotate_left(1) ^ b.rotate_left(2) ^ b.rotate_left(3) ^ b.rotate_left(4) ^ 0x63;
+        i += 1;
+    }
+    sbox
+}
+
+const fn inv_sbox(sbox: &[u8; 256]) -> [u8; 256] {
+    let mut inv = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        inv[sbox[i] as usize] = i as u8;
+        i += 1;
+    }
+    inv
+}
+
+const SBOX: [u8; 256] = sbox();
+const INV_SBOX: [u8; 256] = inv_sbox(&SBOX);
+
+/// Round constants for the key schedule. AES-128 uses the most, at 10.
+const RCON: [u8; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+/// AES with a 128, 192 or 256 bit key.
+///
+/// The state is kept as a flat array of 16 bytes in input order,
+/// so byte `r + 4c` is row `r` of column `c`, as in FIPS-197 §3.4.
+#[derive(Clone)]
+pub struct Aes {
+    rounds: usize,
+    round_keys: [[u8; BLOCK_SIZE]; 15],
+}
+
+impl Aes {
+    /// Expand `key` into the round keys, per FIPS-197 §5.2.
+    ///
+    /// The length of `key` selects AES-128, AES-192 or AES-256.
+    /// Returns [CipherError::InvalidKeyLength] for any length other than 16, 24 or 32 bytes.
+    pub fn new(key: &[u8]) -> Result<Aes, CipherError> {
+        let nk = match key.len() {
+            16 | 24 | 32 => key.len() / 4,
+            len => return Err(CipherError::InvalidKeyLength { len }),
+        };
+        let rounds = nk + 6;
+
+        let mut words: Vec<[u8; 4]> = key
+            .chunks_exact(4)
+            .map(|w| [w[0], w[1], w[2], w[3]])
+            .collect();
+        for i in nk..4 * (rounds + 1) {
+            let mut temp = words[i - 1];
+            if i % nk == 0 {
+                temp.rotate_left(1);
+                temp = temp.map(|b| SBOX[b as usize]);
+                temp[0] ^= RCON[i / nk - 1];
+            } else if nk > 6 && i % nk == 4 {
+                temp = temp.map(|b| SBOX[b as usize]);
+            }
+            let prev = words[i - nk];
+            words.push([
+                prev[0] ^ temp[0],
+                prev[1] ^ temp[1],
+                prev[2] ^ temp[2],
+                prev[3] ^ temp[3],
+            ]);
+        }
+
+        let mut round_keys = [[0; BLOCK_SIZE]; 15];
+        for (round_key, round_words) in round_keys.iter_mut().zip(words.chunks_exact(4)) {
+            for (dst, word) in round_key.chunks_exact_mut(4).zip(round_words) {
+                dst.copy_from_slice(word);
+            }
+        }
+        Ok(Aes { rounds, round_keys })
+    }
+
+    /// Size of the key in bytes: 16, 24 or 32.
+    pub fn key_size(&self) -> usize {
+        (self.rounds - 6) * 4
+    }
+
+    fn add_round_key(&self, state: &mut [u8], round: usize) {
+        for (s, k) in state.iter_mut().zip(self.round_keys[round]) {
+            *s ^= k;
+        }
+    }
+}
+
+fn sub_bytes(state: &mut [u8], sbox: &[u8; 256]) {
+    for s in state.iter_mut() {
+        *s = sbox[*s as usize];
+    }
+}
+
+/// Shift row `r` left by `r` columns, or right when `inverse`.
+fn shift_rows(state: &mut [u8], inverse: bool) {
+    let copy = state.to_vec();
+    for r in 1..4 {
+        for c in 0..4 {
+            let from = if inverse {
+                (c + 4 - r) % 4
+            } else {
+                (c + r) % 4
+            };
+            state[r + 4 * c] = copy[r + 4 * from];
+        }
+    }
+}
+
+/// Multiply each column by the fixed polynomial of FIPS-197 §5.1.3, or its inverse.
+fn mix_columns(state: &mut [u8], inverse: bool) {
+    let coefficients = if inverse {
+        [0x0e, 0x0b, 0x0d, 0x09]
+    } else {
+        [0x02, 0x03, 0x01, 0x01]
+    };
+    for column in state.chunks_exact_mut(4) {
+        let a = [column[0], column[1], column[2], column[3]];
+        for (r, dst) in column.iter_mut().enumerate() {
+            *dst = (0..4).fold(0, |acc, i| {
+                acc ^ gf_mul(coefficients[(4 + i - r) % 4], a[i])
+            });
+        }
+    }
+}
+
+impl BlockCipher for Aes {
+    fn block_size(&self) -> usize {
+        BLOCK_SIZE
+    }
+
+    /// The cipher of FIPS-197 §5.1.
+    fn encrypt_block(&self, block: &mut [u8]) {
+        assert_eq!(block.len(), BLOCK_SIZE, "AES blocks are 16 bytes");
+        self.add_round_key(block, 0);
+        for round in 1..self.rounds {
+            sub_bytes(block, &SBOX);
+            shift_rows(block, false);
+            mix_columns(block, false);
+            self.add_round_key(block, round);
+        }
+        sub_bytes(block, &SBOX);
+        shift_rows(block, false);
+        self.add_round_key(block, self.rounds);
+    }
+
+    /// The inverse cipher of FIPS-197 §5.3.
+    fn decrypt_block(&self, block: &mut [u8]) {
+        assert_eq!(block.len(), BLOCK_SIZE, "AES blocks are 16 bytes");
+        self.add_round_key(block, self.rounds);
+        for round in (1..self.rounds).rev() {
+            shift_rows(block, true);
+            sub_bytes(block, &INV_SBOX);
+            self.add_round_key(block, round);
+            mix_columns(block, true);
+        }
+        shift_rows(block, true);
+        sub_bytes(block, &INV_SBOX);
+        self.add_round_key(block, 0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::adapter::CodecAPI;
+    use crate::codec::hex::Hexadecimal;
+    use crate::utils::fs::read_file;
+
+    fn factory() -> Aes {
+        Aes::new(&Hexadecimal {}.to_plain(b"000102030405060708090a0b0c0d0e0f")).unwrap()
+    }
+
+    /// Known-answer vectors from `_data/aes/kat.txt`, as (key, plaintext, ciphertext).
+    fn known_answers() -> Vec<(Vec<u8>, Vec<u8>, Vec<u8>)> {
+        let mut fields: Vec<Vec<u8>> = Vec::new();
+        let mut vectors = Vec::new();
+        for line in read_file("_data/aes/kat.txt") {
+            let Some((name, value)) = line.split_once(" = ") else {
+                continue;
+            };
+            let value = Hexadecimal {}.try_to_plain(value.as_bytes()).unwrap();
+            let expected = ["KEY", "PLAINTEXT", "CIPHERTEXT"][fields.len()];
+            assert_eq!(name, expected, "fields must come in order");
+            fields.push(value);
+            if fields.len() == 3 {
+                let ciphertext = fields.pop().unwrap();
+                let plaintext = fields.pop().unwrap();
+                let key = fields.pop().unwrap();
+                vectors.push((key, plaintext, ciphertext));
+            }
+        }
+        vectors
+    }
+
+    #[test]
+    fn test_sbox() {
+        assert_eq!(SBOX[0x00], 0x63);
+        assert_eq!(SBOX[0x53], 0xed);
+        assert_eq!(SBOX[0xff], 0x16);
+        assert!((0..=255u8).all(|b| INV_SBOX[SBOX[b as usize] as usize] == b));
+    }
+
+    /// The expanded key of FIPS-197 Appendix A.1.
+    #[test]
+    fn test_key_expansion() {
+        let aes = Aes::new(&Hexadecimal {}.to_plain(b"2b7e151628aed2a6abf7158809cf4f3c")).unwrap();
+        assert_eq!(
+            Hexadecimal {}.encode_to_string(&aes.round_keys[1]),
+            "a0fafe1788542cb123a339392a6c7605"
+        );
+        assert_eq!(
+            Hexadecimal {}.encode_to_string(&aes.round_keys[10]),
+            "d014f9a8c9ee2589e13f0cc8b6630ca6"
+        );
+    }
+
+    #[test]
+    fn test_known_answers() {
+        let vectors = known_answers();
+        assert!(vectors.len() >= 4);
+
+        for (key, plaintext, ciphertext) in vectors {
+            let aes = Aes::new(&key).unwrap();
+            assert_eq!(aes.key_size(), key.len());
+
+            let mut block = plaintext.clone();
+            aes.encrypt_block(&mut block);
+            assert_eq!(block, ciphertext, "encrypting with key {:02x?}", key);
+            aes.decrypt_block(&mut block);
+            assert_eq!(block, plaintext, "decrypting with key {:02x?}", key);
+        }
+    }
+
+    #[test]
+    fn test_invalid_key_length() {
+        for len in [0, 15, 17, 20, 33] {
+            assert_eq!(
+                Aes::new(&vec![0; len]).err(),
+                Some(CipherError::InvalidKeyLength { len })
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_block_length() {
+        factory().encrypt_block(&mut [0; 15]);
+    }
+}
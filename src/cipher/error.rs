@@ -0,0 +1,74 @@
+//! Errors returned by the fallible functions in [crate::cipher].
+
+use std::fmt;
+
+/// Describes why an operation in [crate::cipher] could not be performed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CipherError {
+    /// A key of `len` bytes is not a valid key size for the cipher.
+    InvalidKeyLength { len: usize },
+
+    /// Content of `len` bytes is not a whole number of blocks of `block_size` bytes,
+    /// as a mode without padding requires.
+    UnalignedLength { len: usize, block_size: usize },
+
+    /// An IV or initial counter block of `len` bytes was given,
+    /// where a whole block of `expected` bytes is required.
+    InvalidIvLength { len: usize, expected: usize },
+
+    /// A counter of `len` bytes at `offset` is empty, or doesn't fit in a block of `block_size` bytes.
+    InvalidCounterLayout {
+        offset: usize,
+        len: usize,
+        block_size: usize,
+    },
+
+    /// A cipher with blocks of `block_size` bytes can't be used in this mode.
+    UnsupportedBlockSize { block_size: usize },
+
+    /// An authentication tag of `len` bytes is not a length the mode accepts.
+    InvalidTagLength { len: usize },
+
+    /// The authentication tag doesn't match the content, so it was not decrypted.
+    AuthenticationFailed,
+}
+
+impl fmt::Display for CipherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CipherError::InvalidKeyLength { len } => {
+                write!(f, "invalid key length of {} bytes", len)
+            }
+            CipherError::UnalignedLength { len, block_size } => write!(
+                f,
+                "length {} is not a multiple of the block size {}",
+                len, block_size
+            ),
+            CipherError::InvalidIvLength { len, expected } => {
+                write!(
+                    f,
+                    "invalid IV length of {} bytes, expected {}",
+                    len, expected
+                )
+            }
+            CipherError::InvalidCounterLayout {
+                offset,
+                len,
+                block_size,
+            } => write!(
+                f,
+                "counter of {} bytes at offset {} is empty or doesn't fit in a block of {} bytes",
+                len, offset, block_size
+            ),
+            CipherError::UnsupportedBlockSize { block_size } => {
+                write!(f, "unsupported block size of {} bytes", block_size)
+            }
+            CipherError::InvalidTagLength { len } => {
+                write!(f, "invalid tag length of {} bytes", len)
+            }
+            CipherError::AuthenticationFailed => write!(f, "authentication failed"),
+        }
+    }
+}
+
+impl std::error::Error for CipherError {}
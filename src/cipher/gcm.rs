@@ -0,0 +1,310 @@
+//! Galois/Counter Mode, as specified in [NIST SP 800-38D](https://csrc.nist.gov/pubs/sp/800/38/d/final).
+//!
+//! Authenticated encryption: content is encrypted in CTR mode,
+//! and the ciphertext and associated data are authenticated with a tag computed by [ghash].
+//!
+//! Reusing an IV with the same key leaks the authentication key,
+//! which is what the forbidden-nonce attack exploits.
+
+use super::error::CipherError;
+use super::gf128::Gf128;
+use super::modes::ctr::{CounterLayout, Ctr, Endian};
+use super::modes::process;
+use super::BlockCipher;
+
+/// GCM only works with ciphers with 16 byte blocks.
+pub const BLOCK_SIZE: usize = 16;
+
+/// Size of a full tag in bytes.
+pub const TAG_SIZE: usize = 16;
+
+/// Size of the IV in bytes that GCM uses directly, without hashing it first.
+pub const IV_SIZE: usize = 12;
+
+/// GHASH of SP 800-38D §6.4 with the hash key `h`,
+/// over `aad` and `ciphertext`, each padded with zeros to whole blocks,
+/// followed by a block with both their lengths in bits.
+pub fn ghash(h: Gf128, aad: &[u8], ciphertext: &[u8]) -> Gf128 {
+    let mut lengths = [0; 16];
+    lengths[..8].copy_from_slice(&(aad.len() as u64 * 8).to_be_bytes());
+    lengths[8..].copy_from_slice(&(ciphertext.len() as u64 * 8).to_be_bytes());
+
+    aad.chunks(BLOCK_SIZE)
+        .chain(ciphertext.chunks(BLOCK_SIZE))
+        .chain([&lengths[..]])
+        .fold(Gf128::ZERO, |y, block| (y + Gf128::from_slice(block)) * h)
+}
+
+/// GCM with a keyed block cipher.
+pub struct Gcm<C: BlockCipher> {
+    cipher: C,
+    h: Gf128,
+}
+
+impl<C: BlockCipher> Gcm<C> {
+    /// Derive the hash key from `cipher`.
+    ///
+    /// Returns [CipherError::UnsupportedBlockSize] unless the cipher has 16 byte blocks.
+    pub fn new(cipher: C) -> Result<Gcm<C>, CipherError> {
+        let block_size = cipher.block_size();
+        if block_size != BLOCK_SIZE {
+            return Err(CipherError::UnsupportedBlockSize { block_size });
+        }
+        let mut h = [0; BLOCK_SIZE];
+        cipher.encrypt_block(&mut h);
+        Ok(Gcm {
+            cipher,
+            h: Gf128::from_bytes(h),
+        })
+    }
+
+    /// The hash key, the encryption of the zero block.
+    pub fn hash_key(&self) -> Gf128 {
+        self.h
+    }
+
+    /// Encrypt `plaintext` with `iv`, authenticating it along with `aad`.
+    ///
+    /// Returns the ciphertext, which is as long as `plaintext`, and the full tag.
+    /// IVs of any non-zero length are accepted, but only 12 byte IVs are used directly.
+    pub fn encrypt(
+        &self,
+        iv: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> Result<(Vec<u8>, [u8; TAG_SIZE]), CipherError> {
+        let j0 = self.pre_counter_block(iv)?;
+        let ciphertext = self.gctr(j0, plaintext)?;
+        let tag = self.tag(j0, aad, &ciphertext);
+        Ok((ciphertext, tag))
+    }
+
+    /// Verify `tag` over `aad` and `ciphertext`, then decrypt `ciphertext` with `iv`.
+    ///
+    /// `tag` may be truncated to 4, 8, or 12 to 16 bytes,
+    /// and is compared against as many leading bytes of the computed tag in constant time.
+    /// Returns [CipherError::InvalidTagLength] for any other length,
+    /// and [CipherError::AuthenticationFailed] without decrypting anything if it doesn't match.
+    pub fn decrypt(
+        &self,
+        iv: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+        tag: &[u8],
+    ) -> Result<Vec<u8>, CipherError> {
+        if !matches!(tag.len(), 4 | 8 | 12..=16) {
+            return Err(CipherError::InvalidTagLength { len: tag.len() });
+        }
+        let j0 = self.pre_counter_block(iv)?;
+        let expected = self.tag(j0, aad, ciphertext);
+        if !constant_time_eq(&expected[..tag.len()], tag) {
+            return Err(CipherError::AuthenticationFailed);
+        }
+        self.gctr(j0, ciphertext)
+    }
+
+    /// The pre-counter block J0 of SP 800-38D §7.1:
+    /// a 12 byte `iv` followed by a 32-bit counter of `1`, or the GHASH of any other `iv`.
+    fn pre_counter_block(&self, iv: &[u8]) -> Result<[u8; BLOCK_SIZE], CipherError> {
+        if iv.is_empty() {
+            return Err(CipherError::InvalidIvLength {
+                len: 0,
+                expected: IV_SIZE,
+            });
+        }
+        if iv.len() == IV_SIZE {
+            let mut j0 = [0; BLOCK_SIZE];
+            j0[..IV_SIZE].copy_from_slice(iv);
+            j0[BLOCK_SIZE - 1] = 1;
+            return Ok(j0);
+        }
+        Ok(ghash(self.h, &[], iv).to_bytes())
+    }
+
+    /// Encrypt or decrypt `data` in CTR mode from the block after `j0`,
+    /// incrementing only its last 32 bits.
+    fn gctr(&self, mut j0: [u8; BLOCK_SIZE], data: &[u8]) -> Result<Vec<u8>, CipherError> {
+        let counter = u32::from_be_bytes([j0[12], j0[13], j0[14], j0[15]]).wrapping_add(1);
+        j0[12..].copy_from_slice(&counter.to_be_bytes());
+        let layout = CounterLayout::new(12, 4, Endian::Big);
+        process(Ctr::new(&self.cipher, &j0, layout)?, data)
+    }
+
+    /// The full tag: the GHASH of `aad` and `ciphertext`, masked with the encryption of `j0`.
+    fn tag(&self, j0: [u8; BLOCK_SIZE], aad: &[u8], ciphertext: &[u8]) -> [u8; TAG_SIZE] {
+        let mut mask = j0;
+        self.cipher.encrypt_block(&mut mask);
+        (ghash(self.h, aad, ciphertext) + Gf128::from_bytes(mask)).to_bytes()
+    }
+}
+
+/// Compare `a` and `b`, which are the same length, looking at every byte whatever they contain.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    let diff = a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y));
+    std::hint::black_box(diff) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cipher::aes::Aes;
+    use crate::cipher::modes::vectors::hex;
+    use crate::utils::fs::read_file;
+
+    /// A GCM test vector from `_data/aes/gcm.txt`.
+    struct Vector {
+        key: Vec<u8>,
+        iv: Vec<u8>,
+        aad: Vec<u8>,
+        plaintext: Vec<u8>,
+        ciphertext: Vec<u8>,
+        tag: Vec<u8>,
+    }
+
+    fn factory() -> Gcm<Aes> {
+        Gcm::new(Aes::new(&hex("feffe9928665731c6d6a8f9467308308")).unwrap()).unwrap()
+    }
+
+    fn vectors() -> Vec<Vector> {
+        const FIELDS: [&str; 6] = ["KEY", "IV", "AAD", "PLAINTEXT", "CIPHERTEXT", "TAG"];
+        let mut fields: Vec<Vec<u8>> = Vec::new();
+        let mut vectors = Vec::new();
+        for line in read_file("_data/aes/gcm.txt") {
+            let Some((name, value)) = line.split_once('=') else {
+                continue;
+            };
+            assert_eq!(
+                name.trim(),
+                FIELDS[fields.len()],
+                "fields must come in order"
+            );
+            fields.push(hex(value.trim()));
+            if fields.len() == FIELDS.len() {
+                let mut f = fields.drain(..);
+                let mut next = || f.next().unwrap();
+                vectors.push(Vector {
+                    key: next(),
+                    iv: next(),
+                    aad: next(),
+                    plaintext: next(),
+                    ciphertext: next(),
+                    tag: next(),
+                });
+            }
+        }
+        vectors
+    }
+
+    #[test]
+    fn test_vectors() {
+        let vectors = vectors();
+        assert!(vectors.len() >= 10);
+
+        for v in vectors {
+            let gcm = Gcm::new(Aes::new(&v.key).unwrap()).unwrap();
+            let (ciphertext, tag) = gcm.encrypt(&v.iv, &v.aad, &v.plaintext).unwrap();
+            assert_eq!(
+                ciphertext, v.ciphertext,
+                "encrypting with key {:02x?}",
+                v.key
+            );
+            assert_eq!(tag.to_vec(), v.tag, "tag with key {:02x?}", v.key);
+            assert_eq!(
+                gcm.decrypt(&v.iv, &v.aad, &v.ciphertext, &v.tag),
+                Ok(v.plaintext),
+                "decrypting with key {:02x?}",
+                v.key
+            );
+        }
+    }
+
+    /// Test case 2 of the original GCM specification.
+    #[test]
+    fn test_ghash() {
+        let gcm = Gcm::new(Aes::new(&[0; 16]).unwrap()).unwrap();
+        assert_eq!(
+            gcm.hash_key().to_bytes().to_vec(),
+            hex("66e94bd4ef8a2c3b884cfa59ca342b2e")
+        );
+        assert_eq!(
+            ghash(
+                gcm.hash_key(),
+                &[],
+                &hex("0388dace60b6a392f328c2b971b2fe78")
+            )
+            .to_bytes()
+            .to_vec(),
+            hex("f38cbb1ad69223dcc3457ae5b6b0f885")
+        );
+    }
+
+    #[test]
+    fn test_authentication() {
+        let gcm = factory();
+        let iv = b"unique nonce";
+        let (mut ciphertext, tag) = gcm.encrypt(iv, b"header", b"attack at dawn").unwrap();
+
+        assert_eq!(
+            gcm.decrypt(iv, b"header", &ciphertext, &tag[..12]),
+            Ok(b"attack at dawn".to_vec())
+        );
+        assert_eq!(
+            gcm.decrypt(iv, b"header", &ciphertext, &tag[..4]),
+            Ok(b"attack at dawn".to_vec())
+        );
+        assert_eq!(
+            gcm.decrypt(iv, b"Header", &ciphertext, &tag),
+            Err(CipherError::AuthenticationFailed)
+        );
+        assert_eq!(
+            gcm.decrypt(b"other nonce!", b"header", &ciphertext, &tag),
+            Err(CipherError::AuthenticationFailed)
+        );
+        let mut forged = tag;
+        forged[15] ^= 1;
+        assert_eq!(
+            gcm.decrypt(iv, b"header", &ciphertext, &forged),
+            Err(CipherError::AuthenticationFailed)
+        );
+        ciphertext[0] ^= 1;
+        assert_eq!(
+            gcm.decrypt(iv, b"header", &ciphertext, &tag),
+            Err(CipherError::AuthenticationFailed)
+        );
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        let gcm = factory();
+        for len in [0, 3, 5, 11, 17] {
+            assert_eq!(
+                gcm.decrypt(b"unique nonce", &[], &[], &vec![0; len]),
+                Err(CipherError::InvalidTagLength { len })
+            );
+        }
+        assert_eq!(
+            gcm.encrypt(&[], &[], b"data").err(),
+            Some(CipherError::InvalidIvLength {
+                len: 0,
+                expected: 12
+            })
+        );
+    }
+
+    /// The counter wraps around within its 32 bits, without carrying into the IV.
+    #[test]
+    fn test_counter_wraps() {
+        let gcm = factory();
+        let j0 = [0xff; BLOCK_SIZE];
+        let res = gcm.gctr(j0, &[0; 32]).unwrap();
+
+        let mut expected = [0xff; BLOCK_SIZE];
+        expected[12..].copy_from_slice(&[0; 4]);
+        let mut keystream = expected;
+        gcm.cipher.encrypt_block(&mut keystream);
+        assert_eq!(res[..16], keystream);
+        expected[15] = 1;
+        gcm.cipher.encrypt_block(&mut expected);
+        assert_eq!(res[16..], expected);
+    }
+}
@@ -0,0 +1,174 @@
+//! Arithmetic in GF(2^128), the field GHASH works in,
+//! as specified in [NIST SP 800-38D §6.3](https://csrc.nist.gov/pubs/sp/800/38/d/final).
+//!
+//! Elements are polynomials over GF(2) modulo x^128 + x^7 + x^2 + x + 1.
+//! GCM maps a 16 byte block to an element with its first bit as the coefficient of x^0,
+//! so the bits are reflected compared to the usual order.
+//! [Gf128] keeps that byte order: the block, read as a big-endian [u128],
+//! has the coefficient of x^0 in its most significant bit.
+
+use std::ops::{Add, AddAssign, Mul, MulAssign};
+
+/// The reduction polynomial without its x^128 term, in the bit order of [Gf128].
+const R: u128 = 0xe1 << 120;
+
+/// An element of GF(2^128) in the GCM bit order.
+///
+/// Addition is XOR, so it is its own inverse, and subtraction is addition.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Gf128(u128);
+
+impl Gf128 {
+    pub const ZERO: Gf128 = Gf128(0);
+    pub const ONE: Gf128 = Gf128(1 << 127);
+
+    /// The element for a 16 byte block.
+    pub fn from_bytes(block: [u8; 16]) -> Gf128 {
+        Gf128(u128::from_be_bytes(block))
+    }
+
+    /// The element for a block of up to 16 bytes, padded with zeros at the end.
+    ///
+    /// # Panics
+    ///
+    /// If `block` is longer than 16 bytes.
+    pub fn from_slice(block: &[u8]) -> Gf128 {
+        let mut bytes = [0; 16];
+        bytes[..block.len()].copy_from_slice(block);
+        Gf128::from_bytes(bytes)
+    }
+
+    /// The block for this element.
+    pub fn to_bytes(self) -> [u8; 16] {
+        self.0.to_be_bytes()
+    }
+
+    /// The element whose coefficient of x^`i` is bit `i` of `value`.
+    pub fn from_poly(value: u128) -> Gf128 {
+        Gf128(value.reverse_bits())
+    }
+
+    /// The coefficients of this element, with the coefficient of x^`i` in bit `i`.
+    pub fn to_poly(self) -> u128 {
+        self.0.reverse_bits()
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Raise this element to the power `exp`, by square-and-multiply.
+    pub fn pow(self, mut exp: u128) -> Gf128 {
+        let mut res = Gf128::ONE;
+        let mut base = self;
+        while exp != 0 {
+            if exp & 1 != 0 {
+                res *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+        res
+    }
+
+    /// Multiplicative inverse of this element, which is `self^(2^128 - 2)`.
+    ///
+    /// Returns [None] for zero, which has no inverse.
+    pub fn inverse(self) -> Option<Gf128> {
+        if self.is_zero() {
+            return None;
+        }
+        Some(self.pow(u128::MAX - 1))
+    }
+}
+
+impl From<[u8; 16]> for Gf128 {
+    fn from(block: [u8; 16]) -> Gf128 {
+        Gf128::from_bytes(block)
+    }
+}
+
+impl Add for Gf128 {
+    type Output = Gf128;
+
+    /// Addition in a field of characteristic 2 is XOR.
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, rhs: Gf128) -> Gf128 {
+        Gf128(self.0 ^ rhs.0)
+    }
+}
+
+impl AddAssign for Gf128 {
+    #[allow(clippy::suspicious_op_assign_impl)]
+    fn add_assign(&mut self, rhs: Gf128) {
+        self.0 ^= rhs.0;
+    }
+}
+
+impl Mul for Gf128 {
+    type Output = Gf128;
+
+    /// Multiplication as in Algorithm 1 of SP 800-38D §6.3.
+    ///
+    /// Every bit is processed the same way whatever its value,
+    /// so the time it takes doesn't depend on the operands.
+    fn mul(self, rhs: Gf128) -> Gf128 {
+        let mut res = 0;
+        let mut v = rhs.0;
+        for i in (0..128).rev() {
+            res ^= v & ((self.0 >> i) & 1).wrapping_neg();
+            v = (v >> 1) ^ (R & (v & 1).wrapping_neg());
+        }
+        Gf128(res)
+    }
+}
+
+impl MulAssign for Gf128 {
+    fn mul_assign(&mut self, rhs: Gf128) {
+        *self = *self * rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cipher::modes::vectors::hex;
+
+    fn factory() -> Gf128 {
+        Gf128::from_slice(&hex("66e94bd4ef8a2c3b884cfa59ca342b2e"))
+    }
+
+    #[test]
+    fn test_bit_order() {
+        assert_eq!(Gf128::from_poly(1), Gf128::ONE);
+        assert_eq!(Gf128::ONE.to_bytes()[0], 0x80);
+        assert_eq!(Gf128::from_poly(0b10).to_bytes()[0], 0x40);
+        assert_eq!(factory().to_poly(), factory().0.reverse_bits());
+
+        // x^127 * x = x^128 = x^7 + x^2 + x + 1.
+        assert_eq!(
+            Gf128::from_poly(1 << 127) * Gf128::from_poly(0b10),
+            Gf128::from_poly(0b1000_0111)
+        );
+    }
+
+    #[test]
+    fn test_field() {
+        let a = factory();
+        let b = Gf128::from_slice(b"YELLOW SUBMARINE");
+        let c = Gf128::from_poly(0xdead_beef);
+
+        assert_eq!(a * Gf128::ONE, a);
+        assert_eq!(a * Gf128::ZERO, Gf128::ZERO);
+        assert_eq!(a + a, Gf128::ZERO);
+        assert_eq!(a * b, b * a);
+        assert_eq!((a * b) * c, a * (b * c));
+        assert_eq!(a * (b + c), a * b + a * c);
+        assert_eq!(a.pow(3), a * a * a);
+        assert_eq!(a.pow(0), Gf128::ONE);
+
+        assert_eq!(a * a.inverse().unwrap(), Gf128::ONE);
+        assert_eq!(Gf128::ONE.inverse(), Some(Gf128::ONE));
+        assert_eq!(Gf128::ZERO.inverse(), None);
+    }
+}
@@ -0,0 +1,50 @@
+//! # Cipher
+//!
+//! Block ciphers, implemented in-crate so the attacks in [crate::crack] have something to attack.
+//!
+//! A [crate::cipher::BlockCipher] encrypts and decrypts single blocks.
+//! Modes of operation and attacks are generic over it, so they work with any primitive.
+//! [crate::cipher::gcm] adds authenticated encryption on top,
+//! with the field arithmetic of [crate::cipher::gf128].
+
+pub mod aes;
+pub mod error;
+pub mod gcm;
+pub mod gf128;
+pub mod modes;
+
+/// A keyed block cipher, which encrypts and decrypts one block at a time.
+pub trait BlockCipher {
+    /// Size of a block in bytes.
+    fn block_size(&self) -> usize;
+
+    /// Encrypt `block` in place.
+    ///
+    /// # Panics
+    ///
+    /// If `block` is not exactly [BlockCipher::block_size] bytes long.
+    fn encrypt_block(&self, block: &mut [u8]);
+
+    /// Decrypt `block` in place.
+    ///
+    /// # Panics
+    ///
+    /// If `block` is not exactly [BlockCipher::block_size] bytes long.
+    fn decrypt_block(&self, block: &mut [u8]);
+}
+
+/// A shared reference to a cipher is a cipher too,
+/// so modes can borrow a cipher rather than take ownership of it.
+impl<C: BlockCipher + ?Sized> BlockCipher for &C {
+    fn block_size(&self) -> usize {
+        (**self).block_size()
+    }
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        (**self).encrypt_block(block)
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        (**self).decrypt_block(block)
+    }
+}
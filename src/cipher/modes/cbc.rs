@@ -0,0 +1,119 @@
+//! Cipher Block Chaining mode, SP 800-38A §6.2.
+//!
+//! Every plaintext block is XORed with the previous ciphertext block before it is encrypted,
+//! starting with an explicit IV.
+
+use super::{check_iv, process, xor_in_place, BlockMode};
+use crate::cipher::error::CipherError;
+use crate::cipher::BlockCipher;
+
+/// Encrypt `data`, which must be a whole number of blocks, starting from `iv`.
+pub fn encrypt<C: BlockCipher>(cipher: C, iv: &[u8], data: &[u8]) -> Result<Vec<u8>, CipherError> {
+    process(Encryptor::new(cipher, iv)?, data)
+}
+
+/// Decrypt `data`, which must be a whole number of blocks, starting from `iv`.
+pub fn decrypt<C: BlockCipher>(cipher: C, iv: &[u8], data: &[u8]) -> Result<Vec<u8>, CipherError> {
+    process(Decryptor::new(cipher, iv)?, data)
+}
+
+pub struct Encryptor<C: BlockCipher> {
+    cipher: C,
+    previous: Vec<u8>,
+}
+
+impl<C: BlockCipher> Encryptor<C> {
+    /// Returns [CipherError::InvalidIvLength] unless `iv` is exactly one block long.
+    pub fn new(cipher: C, iv: &[u8]) -> Result<Encryptor<C>, CipherError> {
+        let previous = check_iv(iv, cipher.block_size())?;
+        Ok(Encryptor { cipher, previous })
+    }
+}
+
+impl<C: BlockCipher> BlockMode for Encryptor<C> {
+    fn block_size(&self) -> usize {
+        self.cipher.block_size()
+    }
+
+    fn process_block(&mut self, block: &mut [u8]) {
+        xor_in_place(block, &self.previous);
+        self.cipher.encrypt_block(block);
+        self.previous.copy_from_slice(block);
+    }
+}
+
+pub struct Decryptor<C: BlockCipher> {
+    cipher: C,
+    previous: Vec<u8>,
+}
+
+impl<C: BlockCipher> Decryptor<C> {
+    /// Returns [CipherError::InvalidIvLength] unless `iv` is exactly one block long.
+    pub fn new(cipher: C, iv: &[u8]) -> Result<Decryptor<C>, CipherError> {
+        let previous = check_iv(iv, cipher.block_size())?;
+        Ok(Decryptor { cipher, previous })
+    }
+}
+
+impl<C: BlockCipher> BlockMode for Decryptor<C> {
+    fn block_size(&self) -> usize {
+        self.cipher.block_size()
+    }
+
+    fn process_block(&mut self, block: &mut [u8]) {
+        let ciphertext = block.to_vec();
+        self.cipher.decrypt_block(block);
+        xor_in_place(block, &self.previous);
+        self.previous = ciphertext;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cipher::aes::Aes;
+    use crate::cipher::modes::vectors::*;
+
+    fn factory() -> Aes {
+        aes(KEY_128)
+    }
+
+    /// SP 800-38A F.2.1 to F.2.6.
+    #[test]
+    fn test_sp800_38a() {
+        let cases = [
+            (
+                KEY_128,
+                "7649abac8119b246cee98e9b12e9197d5086cb9b507219ee95db113a917678b2\
+                 73bed6b8e3c1743b7116e69e222295163ff1caa1681fac09120eca307586e1a7",
+            ),
+            (
+                KEY_256,
+                "f58c4c04d6e5f1ba779eabfb5f7bfbd69cfc4e967edb808d679f777bc6702c7d\
+                 39f23369a9d9bacfa530e26304231461b2eb05e2c39be9fcda6c19078c6a9d1b",
+            ),
+        ];
+        for (key, ciphertext) in cases {
+            assert_eq!(
+                encrypt(aes(key), &hex(IV), &hex(PLAINTEXT)),
+                Ok(hex(ciphertext))
+            );
+            assert_eq!(
+                decrypt(aes(key), &hex(IV), &hex(ciphertext)),
+                Ok(hex(PLAINTEXT))
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_iv() {
+        assert_eq!(
+            encrypt(factory(), &[0; 8], &[0; 16]),
+            Err(CipherError::InvalidIvLength {
+                len: 8,
+                expected: 16
+            })
+        );
+        assert!(Decryptor::new(factory(), &[0; 17]).is_err());
+    }
+}
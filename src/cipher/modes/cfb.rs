@@ -0,0 +1,124 @@
+//! Cipher Feedback mode, SP 800-38A §6.3, with full-block feedback (CFB128 for AES).
+//!
+//! The previous ciphertext block, starting with the IV, is encrypted into a keystream block,
+//! so only the cipher's encryption is used, in both directions.
+//! The final block may be partial.
+
+use super::{check_iv, process, xor_in_place, BlockMode};
+use crate::cipher::error::CipherError;
+use crate::cipher::BlockCipher;
+
+/// Encrypt `data` of any length, starting from `iv`.
+pub fn encrypt<C: BlockCipher>(cipher: C, iv: &[u8], data: &[u8]) -> Result<Vec<u8>, CipherError> {
+    process(Encryptor::new(cipher, iv)?, data)
+}
+
+/// Decrypt `data` of any length, starting from `iv`.
+pub fn decrypt<C: BlockCipher>(cipher: C, iv: &[u8], data: &[u8]) -> Result<Vec<u8>, CipherError> {
+    process(Decryptor::new(cipher, iv)?, data)
+}
+
+pub struct Encryptor<C: BlockCipher> {
+    cipher: C,
+    feedback: Vec<u8>,
+}
+
+impl<C: BlockCipher> Encryptor<C> {
+    /// Returns [CipherError::InvalidIvLength] unless `iv` is exactly one block long.
+    pub fn new(cipher: C, iv: &[u8]) -> Result<Encryptor<C>, CipherError> {
+        let feedback = check_iv(iv, cipher.block_size())?;
+        Ok(Encryptor { cipher, feedback })
+    }
+}
+
+impl<C: BlockCipher> BlockMode for Encryptor<C> {
+    fn block_size(&self) -> usize {
+        self.cipher.block_size()
+    }
+
+    fn process_block(&mut self, block: &mut [u8]) {
+        self.cipher.encrypt_block(&mut self.feedback);
+        xor_in_place(block, &self.feedback);
+        self.feedback.copy_from_slice(block);
+    }
+
+    fn process_final(&mut self, rest: &mut [u8]) -> Result<(), CipherError> {
+        self.cipher.encrypt_block(&mut self.feedback);
+        xor_in_place(rest, &self.feedback);
+        Ok(())
+    }
+}
+
+pub struct Decryptor<C: BlockCipher> {
+    cipher: C,
+    feedback: Vec<u8>,
+}
+
+impl<C: BlockCipher> Decryptor<C> {
+    /// Returns [CipherError::InvalidIvLength] unless `iv` is exactly one block long.
+    pub fn new(cipher: C, iv: &[u8]) -> Result<Decryptor<C>, CipherError> {
+        let feedback = check_iv(iv, cipher.block_size())?;
+        Ok(Decryptor { cipher, feedback })
+    }
+}
+
+impl<C: BlockCipher> BlockMode for Decryptor<C> {
+    fn block_size(&self) -> usize {
+        self.cipher.block_size()
+    }
+
+    fn process_block(&mut self, block: &mut [u8]) {
+        let ciphertext = block.to_vec();
+        self.cipher.encrypt_block(&mut self.feedback);
+        xor_in_place(block, &self.feedback);
+        self.feedback = ciphertext;
+    }
+
+    fn process_final(&mut self, rest: &mut [u8]) -> Result<(), CipherError> {
+        self.cipher.encrypt_block(&mut self.feedback);
+        xor_in_place(rest, &self.feedback);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cipher::aes::Aes;
+    use crate::cipher::modes::vectors::*;
+
+    fn factory() -> Aes {
+        aes(KEY_128)
+    }
+
+    /// SP 800-38A F.3.13 and F.3.14.
+    #[test]
+    fn test_sp800_38a() {
+        let ciphertext = hex(
+            "3b3fd92eb72dad20333449f8e83cfb4ac8a64537a0b3a93fcde3cdad9f1ce58b\
+                              26751f67a3cbb140b1808cf187a4f4dfc04b05357c5d1c0eeac4c66f9ff7f2e6",
+        );
+        assert_eq!(
+            encrypt(factory(), &hex(IV), &hex(PLAINTEXT)),
+            Ok(ciphertext.clone())
+        );
+        assert_eq!(
+            decrypt(factory(), &hex(IV), &ciphertext),
+            Ok(hex(PLAINTEXT))
+        );
+    }
+
+    /// A partial final block is a prefix of what the whole block would encrypt to.
+    #[test]
+    fn test_partial_block() {
+        let plaintext = hex(PLAINTEXT);
+        let whole = encrypt(factory(), &hex(IV), &plaintext).unwrap();
+        let partial = encrypt(factory(), &hex(IV), &plaintext[..37]).unwrap();
+
+        assert_eq!(partial, whole[..37]);
+        assert_eq!(
+            decrypt(factory(), &hex(IV), &partial),
+            Ok(plaintext[..37].to_vec())
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! Counter mode, SP 800-38A §6.5.
+//!
+//! A counter block is encrypted into a keystream block, and then incremented,
+//! so encryption and decryption are the same operation. The final block may be partial.
+//!
+//! Where the counter sits in the counter block, and its byte order, differ between protocols,
+//! so they are described by a [CounterLayout].
+//! SP 800-38A increments the whole block as a big-endian number,
+//! while Cryptopals uses a 64-bit little-endian nonce followed by a 64-bit little-endian counter.
+
+use super::{check_iv, process, xor_in_place, BlockMode};
+use crate::cipher::error::CipherError;
+use crate::cipher::BlockCipher;
+
+/// Byte order of a counter.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Endian {
+    Big,
+    Little,
+}
+
+/// Where the counter sits in the counter block, and how it is incremented.
+///
+/// The counter is the `len` bytes from `offset`, and wraps around within them.
+/// The remaining bytes of the counter block are the nonce, and never change.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CounterLayout {
+    offset: usize,
+    len: usize,
+    endian: Endian,
+}
+
+impl CounterLayout {
+    pub fn new(offset: usize, len: usize, endian: Endian) -> CounterLayout {
+        CounterLayout {
+            offset,
+            len,
+            endian,
+        }
+    }
+
+    /// The whole block of `block_size` bytes is a big-endian counter, as in SP 800-38A.
+    pub fn big_endian(block_size: usize) -> CounterLayout {
+        CounterLayout::new(0, block_size, Endian::Big)
+    }
+
+    /// An 8 byte nonce, followed by an 8 byte little-endian counter, as in Cryptopals.
+    pub fn cryptopals() -> CounterLayout {
+        CounterLayout::new(8, 8, Endian::Little)
+    }
+
+    /// Add one to the counter in `block`.
+    fn increment(&self, block: &mut [u8]) {
+        let counter = &mut block[self.offset..self.offset + self.len];
+        // From the least significant byte, carrying for as long as bytes wrap around to 0.
+        for i in 0..counter.len() {
+            let i = match self.endian {
+                Endian::Big => counter.len() - 1 - i,
+                Endian::Little => i,
+            };
+            counter[i] = counter[i].wrapping_add(1);
+            if counter[i] != 0 {
+                break;
+            }
+        }
+    }
+}
+
+/// Encrypt `data` of any length, starting from `initial_block`, the first counter block.
+pub fn encrypt<C: BlockCipher>(
+    cipher: C,
+    initial_block: &[u8],
+    layout: CounterLayout,
+    data: &[u8],
+) -> Result<Vec<u8>, CipherError> {
+    process(Ctr::new(cipher, initial_block, layout)?, data)
+}
+
+/// Decrypt `data` of any length, starting from `initial_block`, the first counter block.
+pub fn decrypt<C: BlockCipher>(
+    cipher: C,
+    initial_block: &[u8],
+    layout: CounterLayout,
+    data: &[u8],
+) -> Result<Vec<u8>, CipherError> {
+    encrypt(cipher, initial_block, layout, data)
+}
+
+/// CTR in either direction.
+pub struct Ctr<C: BlockCipher> {
+    cipher: C,
+    counter_block: Vec<u8>,
+    layout: CounterLayout,
+}
+
+impl<C: BlockCipher> Ctr<C> {
+    /// Returns [CipherError::InvalidIvLength] unless `initial_block` is exactly one block long,
+    /// or [CipherError::InvalidCounterLayout] if the counter is empty or doesn't fit in a block.
+    pub fn new(
+        cipher: C,
+        initial_block: &[u8],
+        layout: CounterLayout,
+    ) -> Result<Ctr<C>, CipherError> {
+        let block_size = cipher.block_size();
+        let counter_block = check_iv(initial_block, block_size)?;
+        if layout.len == 0 || layout.offset.saturating_add(layout.len) > block_size {
+            return Err(CipherError::InvalidCounterLayout {
+                offset: layout.offset,
+                len: layout.len,
+                block_size,
+            });
+        }
+        Ok(Ctr {
+            cipher,
+            counter_block,
+            layout,
+        })
+    }
+
+    /// CTR as Cryptopals specifies it: `nonce` as 8 little-endian bytes,
+    /// then a little-endian block counter from `0`.
+    pub fn cryptopals(cipher: C, nonce: u64) -> Result<Ctr<C>, CipherError> {
+        let mut initial_block = nonce.to_le_bytes().to_vec();
+        initial_block.resize(cipher.block_size(), 0);
+        Ctr::new(cipher, &initial_block, CounterLayout::cryptopals())
+    }
+}
+
+impl<C: BlockCipher> BlockMode for Ctr<C> {
+    fn block_size(&self) -> usize {
+        self.cipher.block_size()
+    }
+
+    fn process_block(&mut self, block: &mut [u8]) {
+        let mut keystream = self.counter_block.clone();
+        self.cipher.encrypt_block(&mut keystream);
+        xor_in_place(block, &keystream);
+        self.layout.increment(&mut self.counter_block);
+    }
+
+    fn process_final(&mut self, rest: &mut [u8]) -> Result<(), CipherError> {
+        self.process_block(rest);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cipher::aes::Aes;
+    use crate::cipher::modes::vectors::*;
+    use crate::codec::adapter::CodecAPI;
+    use crate::codec::b64::Base64;
+
+    const COUNTER: &str = "f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff";
+
+    fn factory() -> Aes {
+        aes(KEY_128)
+    }
+
+    /// SP 800-38A F.5.1, F.5.2, F.5.5 and F.5.6.
+    #[test]
+    fn test_sp800_38a() {
+        let cases = [
+            (
+                KEY_128,
+                "874d6191b620e3261bef6864990db6ce9806f66b7970fdff8617187bb9fffdff\
+                 5ae4df3edbd5d35e5b4f09020db03eab1e031dda2fbe03d1792170a0f3009cee",
+            ),
+            (
+                KEY_256,
+                "601ec313775789a5b7a7f504bbf3d228f443e3ca4d62b59aca84e990cacaf5c5\
+                 2b0930daa23de94ce87017ba2d84988ddfc9c58db67aada613c2dd08457941a6",
+            ),
+        ];
+        let layout = CounterLayout::big_endian(16);
+        for (key, ciphertext) in cases {
+            assert_eq!(
+                encrypt(aes(key), &hex(COUNTER), layout, &hex(PLAINTEXT)),
+                Ok(hex(ciphertext))
+            );
+            assert_eq!(
+                decrypt(aes(key), &hex(COUNTER), layout, &hex(ciphertext)),
+                Ok(hex(PLAINTEXT))
+            );
+        }
+    }
+
+    /// <https://cryptopals.com/sets/3/challenges/18>
+    #[test]
+    fn test_cryptopals() {
+        let ciphertext = Base64 {}
+            .to_plain(b"L77na/nrFsKvynd6HzOoG7GHTLXsTVu9qvY/2syLXzhPweyyMTJULu/6/kXX0KSvoOLSFQ==");
+        let aes = Aes::new(b"YELLOW SUBMARINE").unwrap();
+
+        let res = process(Ctr::cryptopals(&aes, 0).unwrap(), &ciphertext).unwrap();
+
+        assert_eq!(
+            res,
+            "Yo, VIP Let's kick it Ice, Ice, baby Ice, Ice, baby ".as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_increment() {
+        let cases = [
+            (
+                CounterLayout::new(0, 2, Endian::Big),
+                [0x00, 0xff, 0x07],
+                [0x01, 0x00, 0x07],
+            ),
+            (
+                CounterLayout::new(0, 2, Endian::Little),
+                [0xff, 0x00, 0x07],
+                [0x00, 0x01, 0x07],
+            ),
+            (
+                CounterLayout::new(1, 2, Endian::Big),
+                [0x07, 0xff, 0xff],
+                [0x07, 0x00, 0x00],
+            ),
+            (
+                CounterLayout::new(1, 2, Endian::Little),
+                [0x07, 0x05, 0x00],
+                [0x07, 0x06, 0x00],
+            ),
+        ];
+        for (layout, mut block, expected) in cases {
+            layout.increment(&mut block);
+            assert_eq!(block, expected, "{:?}", layout);
+        }
+    }
+
+    #[test]
+    fn test_invalid_layout() {
+        assert_eq!(
+            Ctr::new(factory(), &[0; 16], CounterLayout::new(12, 8, Endian::Big)).err(),
+            Some(CipherError::InvalidCounterLayout {
+                offset: 12,
+                len: 8,
+                block_size: 16
+            })
+        );
+        assert_eq!(
+            Ctr::new(
+                factory(),
+                &[0; 16],
+                CounterLayout::new(8, 0, Endian::Little)
+            )
+            .err(),
+            Some(CipherError::InvalidCounterLayout {
+                offset: 8,
+                len: 0,
+                block_size: 16
+            })
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! Electronic Codebook mode, SP 800-38A §6.1.
+//!
+//! Every block is encrypted on its own, so equal plaintext blocks give equal ciphertext blocks.
+
+use super::{process, BlockMode};
+use crate::cipher::error::CipherError;
+use crate::cipher::BlockCipher;
+
+/// Encrypt `data`, which must be a whole number of blocks.
+pub fn encrypt<C: BlockCipher>(cipher: C, data: &[u8]) -> Result<Vec<u8>, CipherError> {
+    process(Encryptor::new(cipher), data)
+}
+
+/// Decrypt `data`, which must be a whole number of blocks.
+pub fn decrypt<C: BlockCipher>(cipher: C, data: &[u8]) -> Result<Vec<u8>, CipherError> {
+    process(Decryptor::new(cipher), data)
+}
+
+pub struct Encryptor<C: BlockCipher> {
+    cipher: C,
+}
+
+impl<C: BlockCipher> Encryptor<C> {
+    pub fn new(cipher: C) -> Encryptor<C> {
+        Encryptor { cipher }
+    }
+}
+
+impl<C: BlockCipher> BlockMode for Encryptor<C> {
+    fn block_size(&self) -> usize {
+        self.cipher.block_size()
+    }
+
+    fn process_block(&mut self, block: &mut [u8]) {
+        self.cipher.encrypt_block(block);
+    }
+}
+
+pub struct Decryptor<C: BlockCipher> {
+    cipher: C,
+}
+
+impl<C: BlockCipher> Decryptor<C> {
+    pub fn new(cipher: C) -> Decryptor<C> {
+        Decryptor { cipher }
+    }
+}
+
+impl<C: BlockCipher> BlockMode for Decryptor<C> {
+    fn block_size(&self) -> usize {
+        self.cipher.block_size()
+    }
+
+    fn process_block(&mut self, block: &mut [u8]) {
+        self.cipher.decrypt_block(block);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cipher::aes::Aes;
+    use crate::cipher::modes::vectors::*;
+
+    fn factory() -> Aes {
+        aes(KEY_128)
+    }
+
+    /// SP 800-38A F.1.1 to F.1.6.
+    #[test]
+    fn test_sp800_38a() {
+        let cases = [
+            (
+                KEY_128,
+                "3ad77bb40d7a3660a89ecaf32466ef97f5d3d58503b9699de785895a96fdbaaf\
+                 43b1cd7f598ece23881b00e3ed0306887b0c785e27e8ad3f8223207104725dd4",
+            ),
+            (
+                KEY_256,
+                "f3eed1bdb5d2a03c064b5a7e3db181f8591ccb10d410ed26dc5ba74a31362870\
+                 b6ed21b99ca6f4f9f153e7b1beafed1d23304b7a39f9f3ff067d8d8f9e24ecc7",
+            ),
+        ];
+        for (key, ciphertext) in cases {
+            assert_eq!(encrypt(aes(key), &hex(PLAINTEXT)), Ok(hex(ciphertext)));
+            assert_eq!(decrypt(aes(key), &hex(ciphertext)), Ok(hex(PLAINTEXT)));
+        }
+    }
+
+    #[test]
+    fn test_repeated_blocks() {
+        let ciphertext = encrypt(factory(), &[b'A'; 48]).unwrap();
+        assert_eq!(ciphertext[..16], ciphertext[16..32]);
+        assert_eq!(ciphertext[..16], ciphertext[32..]);
+
+        assert_eq!(
+            encrypt(factory(), &[0; 17]),
+            Err(CipherError::UnalignedLength {
+                len: 17,
+                block_size: 16
+            })
+        );
+    }
+}
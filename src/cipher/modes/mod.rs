@@ -0,0 +1,188 @@
+//! Block cipher modes of operation, as specified in
+//! [NIST SP 800-38A](https://csrc.nist.gov/pubs/sp/800/38/a/final).
+//!
+//! Each mode has one-shot `encrypt` and `decrypt` functions over byte slices,
+//! and [BlockMode] implementations that process content incrementally through [Streaming].
+//! Modes work with any [BlockCipher], and borrow it when given a reference.
+//!
+//! ECB and CBC only process whole blocks, and don't pad content themselves.
+//! CTR, CFB and OFB turn the block cipher into a stream cipher, so content may be any length.
+//!
+//! [BlockCipher]: crate::cipher::BlockCipher
+
+pub mod cbc;
+pub mod cfb;
+pub mod ctr;
+pub mod ecb;
+pub mod ofb;
+
+use super::error::CipherError;
+
+/// A mode of operation in one direction, with the chaining state it keeps between blocks.
+pub trait BlockMode {
+    /// Size of a block in bytes, as given by the underlying cipher.
+    fn block_size(&self) -> usize;
+
+    /// Encrypt or decrypt one whole block in place.
+    fn process_block(&mut self, block: &mut [u8]);
+
+    /// Encrypt or decrypt the final `rest` of the content in place,
+    /// which is shorter than a block, and may be empty.
+    ///
+    /// By default, only an empty `rest` is accepted,
+    /// as modes that process whole blocks can't do anything with the rest.
+    fn process_final(&mut self, rest: &mut [u8]) -> Result<(), CipherError> {
+        if rest.is_empty() {
+            return Ok(());
+        }
+        Err(CipherError::UnalignedLength {
+            len: rest.len(),
+            block_size: self.block_size(),
+        })
+    }
+}
+
+/// Incremental encryption or decryption with a [BlockMode].
+///
+/// Content is fed in pieces of any size with [Streaming::update],
+/// which returns as much output as the whole blocks received so far allow.
+/// [Streaming::finish] processes whatever is left.
+pub struct Streaming<M: BlockMode> {
+    mode: M,
+    pending: Vec<u8>,
+    processed: usize,
+}
+
+impl<M: BlockMode> Streaming<M> {
+    pub fn new(mode: M) -> Streaming<M> {
+        Streaming {
+            mode,
+            pending: Vec::new(),
+            processed: 0,
+        }
+    }
+
+    /// Feed `input` to the mode, returning the output for every block completed by it.
+    pub fn update(&mut self, input: &[u8]) -> Vec<u8> {
+        self.pending.extend_from_slice(input);
+        let block_size = self.mode.block_size();
+        let whole = self.pending.len() / block_size * block_size;
+
+        let mut res = self.pending.drain(..whole).collect::<Vec<u8>>();
+        for block in res.chunks_exact_mut(block_size) {
+            self.mode.process_block(block);
+        }
+        self.processed += res.len();
+        res
+    }
+
+    /// Process the rest of the content, which is shorter than a block.
+    ///
+    /// Modes that only process whole blocks fail with [CipherError::UnalignedLength]
+    /// if there is any content left, with the total length of the content.
+    pub fn finish(mut self) -> Result<Vec<u8>, CipherError> {
+        let mut rest = std::mem::take(&mut self.pending);
+        let total = self.processed + rest.len();
+        let block_size = self.mode.block_size();
+        self.mode.process_final(&mut rest).map_err(|e| match e {
+            CipherError::UnalignedLength { .. } => CipherError::UnalignedLength {
+                len: total,
+                block_size,
+            },
+            _ => e,
+        })?;
+        Ok(rest)
+    }
+}
+
+/// Run `mode` over the whole of `data` at once.
+pub fn process<M: BlockMode>(mode: M, data: &[u8]) -> Result<Vec<u8>, CipherError> {
+    let mut stream = Streaming::new(mode);
+    let mut res = stream.update(data);
+    res.extend(stream.finish()?);
+    Ok(res)
+}
+
+/// Copy `iv` into a block, checking that it is exactly `block_size` bytes long.
+fn check_iv(iv: &[u8], block_size: usize) -> Result<Vec<u8>, CipherError> {
+    if iv.len() != block_size {
+        return Err(CipherError::InvalidIvLength {
+            len: iv.len(),
+            expected: block_size,
+        });
+    }
+    Ok(iv.to_vec())
+}
+
+/// XOR `other` into `block`, byte by byte.
+fn xor_in_place(block: &mut [u8], other: &[u8]) {
+    for (b, o) in block.iter_mut().zip(other) {
+        *b ^= o;
+    }
+}
+
+/// Shared test vectors from NIST SP 800-38A Appendix F.
+#[cfg(test)]
+pub(crate) mod vectors {
+    use crate::cipher::aes::Aes;
+    use crate::codec::adapter::CodecAPI;
+    use crate::codec::hex::Hexadecimal;
+
+    pub const KEY_128: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+    pub const KEY_256: &str = "603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4";
+    pub const IV: &str = "000102030405060708090a0b0c0d0e0f";
+    pub const PLAINTEXT: &str = "6bc1bee22e409f96e93d7e117393172a\
+                                 ae2d8a571e03ac9c9eb76fac45af8e51\
+                                 30c81c46a35ce411e5fbc1191a0a52ef\
+                                 f69f2445df4f9b17ad2b417be66c3710";
+
+    pub fn hex(s: &str) -> Vec<u8> {
+        Hexadecimal {}.try_to_plain(s.as_bytes()).unwrap()
+    }
+
+    pub fn aes(key: &str) -> Aes {
+        Aes::new(&hex(key)).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::vectors::*;
+    use super::*;
+    use crate::cipher::aes::Aes;
+
+    fn factory() -> Aes {
+        aes(KEY_128)
+    }
+
+    /// Every way of splitting the content into pieces gives the same output.
+    #[test]
+    fn test_streaming_piece_sizes() {
+        let plaintext = hex(PLAINTEXT);
+        let expected = cbc::encrypt(factory(), &hex(IV), &plaintext).unwrap();
+
+        for piece in 1..=plaintext.len() {
+            let mut stream = Streaming::new(cbc::Encryptor::new(factory(), &hex(IV)).unwrap());
+            let mut res = Vec::new();
+            for chunk in plaintext.chunks(piece) {
+                res.extend(stream.update(chunk));
+            }
+            res.extend(stream.finish().unwrap());
+            assert_eq!(res, expected, "pieces of {} bytes", piece);
+        }
+    }
+
+    #[test]
+    fn test_streaming_unaligned() {
+        let mut stream = Streaming::new(ecb::Encryptor::new(factory()));
+        assert_eq!(stream.update(&[0; 20]).len(), 16);
+        assert_eq!(stream.update(&[0; 3]), Vec::<u8>::new());
+        assert_eq!(
+            stream.finish(),
+            Err(CipherError::UnalignedLength {
+                len: 23,
+                block_size: 16
+            })
+        );
+    }
+}
@@ -0,0 +1,81 @@
+//! Output Feedback mode, SP 800-38A §6.4.
+//!
+//! The IV is encrypted over and over into a keystream,
+//! which is XORed with the content, so encryption and decryption are the same operation.
+//! The final block may be partial.
+
+use super::{check_iv, process, xor_in_place, BlockMode};
+use crate::cipher::error::CipherError;
+use crate::cipher::BlockCipher;
+
+/// Encrypt `data` of any length, starting from `iv`.
+pub fn encrypt<C: BlockCipher>(cipher: C, iv: &[u8], data: &[u8]) -> Result<Vec<u8>, CipherError> {
+    process(Ofb::new(cipher, iv)?, data)
+}
+
+/// Decrypt `data` of any length, starting from `iv`.
+pub fn decrypt<C: BlockCipher>(cipher: C, iv: &[u8], data: &[u8]) -> Result<Vec<u8>, CipherError> {
+    encrypt(cipher, iv, data)
+}
+
+/// OFB in either direction.
+pub struct Ofb<C: BlockCipher> {
+    cipher: C,
+    keystream: Vec<u8>,
+}
+
+impl<C: BlockCipher> Ofb<C> {
+    /// Returns [CipherError::InvalidIvLength] unless `iv` is exactly one block long.
+    pub fn new(cipher: C, iv: &[u8]) -> Result<Ofb<C>, CipherError> {
+        let keystream = check_iv(iv, cipher.block_size())?;
+        Ok(Ofb { cipher, keystream })
+    }
+}
+
+impl<C: BlockCipher> BlockMode for Ofb<C> {
+    fn block_size(&self) -> usize {
+        self.cipher.block_size()
+    }
+
+    fn process_block(&mut self, block: &mut [u8]) {
+        self.cipher.encrypt_block(&mut self.keystream);
+        xor_in_place(block, &self.keystream);
+    }
+
+    fn process_final(&mut self, rest: &mut [u8]) -> Result<(), CipherError> {
+        self.process_block(rest);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cipher::aes::Aes;
+    use crate::cipher::modes::vectors::*;
+
+    fn factory() -> Aes {
+        aes(KEY_128)
+    }
+
+    /// SP 800-38A F.4.1 and F.4.2.
+    #[test]
+    fn test_sp800_38a() {
+        let ciphertext = hex(
+            "3b3fd92eb72dad20333449f8e83cfb4a7789508d16918f03f53c52dac54ed825\
+                              9740051e9c5fecf64344f7a82260edcc304c6528f659c77866a510d9c1d6ae5e",
+        );
+        assert_eq!(
+            encrypt(factory(), &hex(IV), &hex(PLAINTEXT)),
+            Ok(ciphertext.clone())
+        );
+        assert_eq!(
+            decrypt(factory(), &hex(IV), &ciphertext),
+            Ok(hex(PLAINTEXT))
+        );
+        assert_eq!(
+            encrypt(factory(), &hex(IV), &hex(PLAINTEXT)[..5]),
+            Ok(ciphertext[..5].to_vec())
+        );
+    }
+}
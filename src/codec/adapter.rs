@@ -1,8 +1,12 @@
+use super::error::CodecError;
+
 /// # Codec adapter
 /// Implement Codec to provide an adapter that
 /// implements the ability to encode and decode a given encoding format.
-/// This module provides implementations for [Base64][crate::codec::b64::Base64]
-/// and [Hexadecimal][crate::codec::hex::Hexadecimal] encodings.
+/// This module provides implementations for [Base64][crate::codec::b64::Base64],
+/// [Base32][crate::codec::b32::Base32]
+/// and [Hexadecimal][crate::codec::hex::Hexadecimal] encodings,
+/// and a [Raw][crate::codec::raw::Raw] codec for content that isn't encoded at all.
 ///
 /// Codecs should allow you to:
 /// - Transform a byte slice of UTF-8 code points to code points for the target encoding format.
@@ -51,6 +55,36 @@ pub trait CodecAPI {
         4
     }
 
+    /// Helper that returns the number of encoded bytes that decode independently
+    /// of the rest of the encoded content, i.e. the size of one quantum.
+    ///
+    /// By default, returns `4`, which is what [CodecAPI::to_plain] chunks by.
+    /// For example:
+    /// - [crate::codec::b64::Base64] encodes 3 bytes to 4 characters, so keeps the default
+    /// - [crate::codec::b32::Base32] changes this to 8 because Base32 encodes 5 bytes to 8 characters
+    ///
+    /// Used by the streaming adapters in [crate::codec::stream] to buffer incomplete quanta.
+    fn get_encoded_chunksize(&self) -> usize {
+        4
+    }
+
+    /// Whether the encoded content is text, which may be split into lines.
+    ///
+    /// By default, returns `true`.
+    /// Codecs for binary content, like [crate::codec::raw::Raw], change this to `false`,
+    /// so that bytes that happen to be `\n` aren't treated as line breaks.
+    fn is_textual(&self) -> bool {
+        true
+    }
+
+    /// Whether decoding skips over `c` instead of treating it as encoded content,
+    /// like [crate::codec::wrap::Wrapped] does with line breaks.
+    ///
+    /// By default, no bytes are skipped.
+    fn skips(&self, _c: u8) -> bool {
+        false
+    }
+
     /// Encode a byte slice using [CodecAPI::raw_encode].
     fn encode(&self, data: &[u8]) -> Vec<u8> {
         data.chunks(self.get_chunksize())
@@ -82,4 +116,100 @@ pub trait CodecAPI {
             .map(|v| (*v as char).to_string())
             .collect::<String>()
     }
+
+    /// Fallible counterpart to [CodecAPI::encode].
+    ///
+    /// Encoding arbitrary bytes cannot fail for the codecs in this crate,
+    /// so by default this just wraps [CodecAPI::encode].
+    fn try_encode(&self, data: &[u8]) -> Result<Vec<u8>, CodecError> {
+        Ok(self.encode(data))
+    }
+
+    /// Encode `data` as the continuation of `offset` bytes of content that were already encoded,
+    /// for encoders that work through content a piece at a time.
+    /// `offset` MUST be a multiple of [CodecAPI::get_chunksize].
+    ///
+    /// By default, the encoding of a chunk doesn't depend on what came before it,
+    /// so this just wraps [CodecAPI::try_encode].
+    /// Codecs that lay out their output as a whole, like [crate::codec::wrap::Wrapped], override it.
+    fn try_encode_at(&self, data: &[u8], offset: usize) -> Result<Vec<u8>, CodecError> {
+        let _ = offset;
+        self.try_encode(data)
+    }
+
+    /// Fallible counterpart to [CodecAPI::to_plain].
+    ///
+    /// Unlike [CodecAPI::to_plain], which skips characters it can't map,
+    /// implementations MUST reject malformed input with a [CodecError].
+    fn try_to_plain(&self, data: &[u8]) -> Result<Vec<u8>, CodecError>;
+
+    /// Convenience function that wraps [CodecAPI::try_to_plain]
+    /// to decode a byte slice as a String in the target encoding format.
+    fn try_to_plain_string(&self, data: &[u8]) -> Result<String, CodecError> {
+        Ok(self
+            .try_to_plain(data)?
+            .iter()
+            .map(|v| *v as char)
+            .collect::<String>())
+    }
 }
+
+/// A shared reference to a codec is a codec too,
+/// so adapters like [crate::codec::wrap::Wrapped] can borrow a codec rather than take ownership of it.
+impl<C: CodePointMap + ?Sized> CodePointMap for &C {
+    fn map_codepoint_to_plain(&self, v: u8) -> Option<u8> {
+        (**self).map_codepoint_to_plain(v)
+    }
+
+    fn map_plain_to_codepoint(&self, c: u8) -> Option<u8> {
+        (**self).map_plain_to_codepoint(c)
+    }
+}
+
+impl<C: CodecAPI + ?Sized> CodecAPI for &C {
+    fn raw_encode(&self, v: &[u8]) -> Vec<u8> {
+        (**self).raw_encode(v)
+    }
+
+    fn raw_to_plain(&self, v: &[u8]) -> Vec<u8> {
+        (**self).raw_to_plain(v)
+    }
+
+    fn get_chunksize(&self) -> usize {
+        (**self).get_chunksize()
+    }
+
+    fn get_encoded_chunksize(&self) -> usize {
+        (**self).get_encoded_chunksize()
+    }
+
+    fn is_textual(&self) -> bool {
+        (**self).is_textual()
+    }
+
+    fn skips(&self, c: u8) -> bool {
+        (**self).skips(c)
+    }
+
+    fn encode(&self, data: &[u8]) -> Vec<u8> {
+        (**self).encode(data)
+    }
+
+    fn to_plain(&self, data: &[u8]) -> Vec<u8> {
+        (**self).to_plain(data)
+    }
+
+    fn try_encode(&self, data: &[u8]) -> Result<Vec<u8>, CodecError> {
+        (**self).try_encode(data)
+    }
+
+    fn try_encode_at(&self, data: &[u8], offset: usize) -> Result<Vec<u8>, CodecError> {
+        (**self).try_encode_at(data, offset)
+    }
+
+    fn try_to_plain(&self, data: &[u8]) -> Result<Vec<u8>, CodecError> {
+        (**self).try_to_plain(data)
+    }
+}
+
+impl<C: Codec + ?Sized> Codec for &C {}
@@ -0,0 +1,408 @@
+use super::adapter::{CodePointMap, Codec, CodecAPI};
+use super::error::CodecError;
+
+const UPPERCASEOFFSET: u8 = b'A'; // b'A' is 65 in utf-8, but 0 in Base32 (and 10 in Base32hex).
+const LOWERCASEOFFSET: u8 = b'a'; // Base32 is case insensitive, so b'a' decodes like b'A'.
+const BASE32DIGITOFFSET: u8 = b'2' - 26; // b'2' is 50 in utf-8, but represents 26 in Base32. So the offset is b'2'-26=24.
+const DIGITOFFSET: u8 = b'0'; // b'0' is 48 in utf-8, and represents 0 in Base32hex.
+const PADDING: u8 = b'=';
+
+/// Number of Base32 characters in a full quantum, each holding 5 bits.
+const QUANTUMSIZE: usize = 8;
+
+/// Base32 encoding, using the alphabet from
+/// [RFC 4648 §6](https://www.rfc-editor.org/rfc/rfc4648#section-6).
+#[derive(Copy, Clone)]
+pub struct Base32;
+
+/// Base32 encoding with the "Extended Hex" alphabet from
+/// [RFC 4648 §7](https://www.rfc-editor.org/rfc/rfc4648#section-7),
+/// which preserves the sort order of the encoded data.
+#[derive(Copy, Clone)]
+pub struct Base32Hex;
+
+impl CodePointMap for Base32 {
+    fn map_codepoint_to_plain(&self, v: u8) -> Option<u8> {
+        match v {
+            0..=25 => Some(v + UPPERCASEOFFSET),
+            26..=31 => Some(v + BASE32DIGITOFFSET),
+            _ => None,
+        }
+    }
+
+    fn map_plain_to_codepoint(&self, c: u8) -> Option<u8> {
+        match c {
+            b'A'..=b'Z' => Some(c - UPPERCASEOFFSET),
+            b'a'..=b'z' => Some(c - LOWERCASEOFFSET),
+            b'2'..=b'7' => Some(c - BASE32DIGITOFFSET),
+            _ => None, // also ignores PADDING
+        }
+    }
+}
+
+impl CodePointMap for Base32Hex {
+    fn map_codepoint_to_plain(&self, v: u8) -> Option<u8> {
+        match v {
+            0..=9 => Some(v + DIGITOFFSET),
+            10..=31 => Some(v - 10 + UPPERCASEOFFSET),
+            _ => None,
+        }
+    }
+
+    fn map_plain_to_codepoint(&self, c: u8) -> Option<u8> {
+        match c {
+            b'0'..=b'9' => Some(c - DIGITOFFSET),
+            b'A'..=b'V' => Some(c - UPPERCASEOFFSET + 10),
+            b'a'..=b'v' => Some(c - LOWERCASEOFFSET + 10),
+            _ => None, // also ignores PADDING
+        }
+    }
+}
+
+impl CodecAPI for Base32 {
+    /// Bitwise operations to expand data in 5-byte chunks operating
+    /// in an 8-bit space to 8-byte chunks operating in a 5-bit space.
+    fn raw_encode(&self, chunk: &[u8]) -> Vec<u8> {
+        encode_quantum(self, chunk)
+    }
+
+    /// Bitwise operations to collapse a quantum of 2 to 8 code points operating
+    /// in a 5-bit space to 1 to 5 bytes operating in an 8-bit space.
+    fn raw_to_plain(&self, chunk: &[u8]) -> Vec<u8> {
+        decode_codepoints(chunk)
+    }
+
+    /// Set expected length of byte chunks to 5.
+    /// See [crate::codec::adapter::CodecAPI::get_chunksize].
+    fn get_chunksize(&self) -> usize {
+        5
+    }
+
+    /// Base32 quanta are 8 characters long.
+    /// See [crate::codec::adapter::CodecAPI::get_encoded_chunksize].
+    fn get_encoded_chunksize(&self) -> usize {
+        QUANTUMSIZE
+    }
+
+    /// Base32 quanta are 8 characters long, so [crate::codec::adapter::CodecAPI::to_plain]
+    /// can't be used as is.
+    fn to_plain(&self, data: &[u8]) -> Vec<u8> {
+        to_plain_lossy(self, data)
+    }
+
+    /// Decode Base32 strictly, one 8-character quantum at a time.
+    ///
+    /// Decoding is case insensitive.
+    /// The final quantum may omit its padding,
+    /// but it MUST still contain a number of characters that a whole number of bytes encodes to.
+    fn try_to_plain(&self, data: &[u8]) -> Result<Vec<u8>, CodecError> {
+        try_decode(self, data)
+    }
+}
+
+impl CodecAPI for Base32Hex {
+    /// See [Base32::raw_encode].
+    fn raw_encode(&self, chunk: &[u8]) -> Vec<u8> {
+        encode_quantum(self, chunk)
+    }
+
+    /// See [Base32::raw_to_plain].
+    fn raw_to_plain(&self, chunk: &[u8]) -> Vec<u8> {
+        decode_codepoints(chunk)
+    }
+
+    fn get_chunksize(&self) -> usize {
+        5
+    }
+
+    fn get_encoded_chunksize(&self) -> usize {
+        QUANTUMSIZE
+    }
+
+    fn to_plain(&self, data: &[u8]) -> Vec<u8> {
+        to_plain_lossy(self, data)
+    }
+
+    /// See [Base32::try_to_plain].
+    fn try_to_plain(&self, data: &[u8]) -> Result<Vec<u8>, CodecError> {
+        try_decode(self, data)
+    }
+}
+
+impl Codec for Base32 {}
+impl Codec for Base32Hex {}
+
+/// Number of characters that `bytes` bytes encode to, before padding.
+fn encoded_len(bytes: usize) -> usize {
+    (bytes * 8).div_ceil(5)
+}
+
+/// Whether a quantum of `len` characters decodes to a whole number of bytes.
+/// Only 2, 4, 5, 7 and 8 characters can be produced by [encode_quantum].
+fn is_valid_quantum_len(len: usize) -> bool {
+    matches!(len, 2 | 4 | 5 | 7 | 8)
+}
+
+/// Expand up to 5 bytes into a padded quantum of Base32 characters from the alphabet of `map`.
+fn encode_quantum<M: CodePointMap>(map: &M, chunk: &[u8]) -> Vec<u8> {
+    // Left-align the chunk in a 40-bit buffer, so that the 5-bit groups can be read off the top.
+    let buffer = chunk
+        .iter()
+        .enumerate()
+        .fold(0u64, |acc, (i, c)| acc | (*c as u64) << (32 - 8 * i));
+
+    let mut res = (0..encoded_len(chunk.len()))
+        .map(|i| ((buffer >> (35 - 5 * i)) & 0b00011111) as u8)
+        .filter_map(|c| map.map_codepoint_to_plain(c))
+        .collect::<Vec<u8>>();
+
+    while res.len() < QUANTUMSIZE {
+        res.push(PADDING);
+    }
+    res
+}
+
+/// See [Base32::raw_to_plain].
+fn decode_codepoints(chunk: &[u8]) -> Vec<u8> {
+    let buffer = chunk.iter().enumerate().fold(0u64, |acc, (i, c)| {
+        acc | ((c & 0b00011111) as u64) << (35 - 5 * i)
+    });
+
+    (0..chunk.len() * 5 / 8)
+        .map(|i| (buffer >> (32 - 8 * i)) as u8)
+        .collect()
+}
+
+/// See [Base32::to_plain].
+fn to_plain_lossy<M: CodePointMap>(map: &M, data: &[u8]) -> Vec<u8> {
+    data.chunks(QUANTUMSIZE)
+        .map(|c| {
+            c.iter()
+                .filter(|c| **c != PADDING)
+                .filter_map(|c| map.map_plain_to_codepoint(*c))
+                .collect::<Vec<u8>>()
+        })
+        .flat_map(|c| decode_codepoints(&c))
+        .collect()
+}
+
+/// See [Base32::try_to_plain].
+fn try_decode<M: CodePointMap>(map: &M, data: &[u8]) -> Result<Vec<u8>, CodecError> {
+    let mut res: Vec<u8> = Vec::with_capacity(data.len() / QUANTUMSIZE * 5);
+    for (i, quantum) in data.chunks(QUANTUMSIZE).enumerate() {
+        let offset = i * QUANTUMSIZE;
+        let is_last = offset + quantum.len() == data.len();
+        let codepoints = try_map_quantum(map, quantum, offset, is_last)?;
+        res.extend(decode_codepoints(&codepoints));
+    }
+    Ok(res)
+}
+
+/// Map a single quantum of Base32 characters to 5-bit code points,
+/// validating the alphabet and the position of any padding.
+///
+/// `offset` is the position of `quantum` in the whole input, used for error reporting.
+fn try_map_quantum<M: CodePointMap>(
+    map: &M,
+    quantum: &[u8],
+    offset: usize,
+    is_last: bool,
+) -> Result<Vec<u8>, CodecError> {
+    let mut codepoints: Vec<u8> = Vec::with_capacity(QUANTUMSIZE);
+    for (i, c) in quantum.iter().enumerate() {
+        if *c == PADDING {
+            // Padding may only complete the final quantum,
+            // right after a number of characters that encodes whole bytes.
+            if !is_last || !is_valid_quantum_len(codepoints.len()) {
+                return Err(CodecError::InvalidPadding { offset: offset + i });
+            }
+            if quantum.len() < QUANTUMSIZE {
+                return Err(CodecError::TruncatedQuantum {
+                    offset,
+                    len: quantum.len(),
+                });
+            }
+            continue;
+        }
+        if codepoints.len() < i {
+            // A data character follows padding, so the padding was misplaced.
+            return Err(CodecError::InvalidPadding {
+                offset: offset + codepoints.len(),
+            });
+        }
+        match map.map_plain_to_codepoint(*c) {
+            Some(v) => codepoints.push(v),
+            None => {
+                return Err(CodecError::InvalidCharacter {
+                    offset: offset + i,
+                    byte: *c,
+                })
+            }
+        }
+    }
+
+    if !is_valid_quantum_len(codepoints.len()) {
+        return Err(CodecError::TruncatedQuantum {
+            offset,
+            len: codepoints.len(),
+        });
+    }
+    Ok(codepoints)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Base32, Base32Hex};
+    use crate::codec::adapter::CodecAPI;
+    use crate::codec::error::CodecError;
+    use crate::codec::hex::Hexadecimal;
+
+    fn factory() -> Base32 {
+        Base32 {}
+    }
+
+    /// Test vectors from <https://www.rfc-editor.org/rfc/rfc4648#section-10>
+    const RFC4648_VECTORS: [(&str, &str, &str); 7] = [
+        ("", "", ""),
+        ("f", "MY======", "CO======"),
+        ("fo", "MZXQ====", "CPNG===="),
+        ("foo", "MZXW6===", "CPNMU==="),
+        ("foob", "MZXW6YQ=", "CPNMUOG="),
+        ("fooba", "MZXW6YTB", "CPNMUOJ1"),
+        ("foobar", "MZXW6YTBOI======", "CPNMUOJ1E8======"),
+    ];
+
+    #[test]
+    fn test_encode_rfc4648() {
+        for case in RFC4648_VECTORS {
+            assert_eq!(factory().encode_to_string(case.0.as_bytes()), case.1);
+            assert_eq!(Base32Hex {}.encode_to_string(case.0.as_bytes()), case.2);
+        }
+    }
+
+    #[test]
+    fn test_decode_rfc4648() {
+        for case in RFC4648_VECTORS {
+            assert_eq!(factory().to_plain_string(case.1.as_bytes()), case.0);
+            assert_eq!(
+                factory().try_to_plain_string(case.1.as_bytes()),
+                Ok(case.0.to_string())
+            );
+            assert_eq!(
+                Base32Hex {}.try_to_plain_string(case.2.as_bytes()),
+                Ok(case.0.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_case_insensitive() {
+        assert_eq!(
+            factory().try_to_plain_string("mzxw6ytboi======".as_bytes()),
+            Ok("foobar".to_string())
+        );
+        assert_eq!(
+            Base32Hex {}.try_to_plain_string("cpnmuoj1e8======".as_bytes()),
+            Ok("foobar".to_string())
+        );
+    }
+
+    #[test]
+    fn test_decode_preserves_zero_bytes() {
+        let input: &[u8] = &[0, 0, 0, 0, 0, 0];
+        let encoded = factory().encode(input);
+
+        assert_eq!(encoded, "AAAAAAAAAA======".as_bytes());
+        assert_eq!(factory().try_to_plain(&encoded), Ok(input.to_vec()));
+    }
+
+    #[test]
+    fn test_try_decode_invalid_character() {
+        let cases = [
+            (
+                "MZXW1YTB",
+                CodecError::InvalidCharacter {
+                    offset: 4,
+                    byte: b'1',
+                },
+            ),
+            (
+                "MZXW6YTB8I======",
+                CodecError::InvalidCharacter {
+                    offset: 8,
+                    byte: b'8',
+                },
+            ),
+        ];
+        for case in cases {
+            assert_eq!(factory().try_to_plain(case.0.as_bytes()), Err(case.1));
+        }
+        assert_eq!(
+            Base32Hex {}.try_to_plain("CPNMUOJW".as_bytes()),
+            Err(CodecError::InvalidCharacter {
+                offset: 7,
+                byte: b'W'
+            })
+        );
+    }
+
+    #[test]
+    fn test_try_decode_invalid_padding() {
+        let cases = [
+            ("MY======MZXW6YTB", CodecError::InvalidPadding { offset: 2 }),
+            ("MZX=====", CodecError::InvalidPadding { offset: 3 }),
+            ("M=======", CodecError::InvalidPadding { offset: 1 }),
+            ("MZXQ==Q=", CodecError::InvalidPadding { offset: 4 }),
+        ];
+        for case in cases {
+            assert_eq!(factory().try_to_plain(case.0.as_bytes()), Err(case.1));
+        }
+    }
+
+    #[test]
+    fn test_try_decode_truncated_quantum() {
+        let cases = [
+            (
+                "MZXW6YTBO",
+                CodecError::TruncatedQuantum { offset: 8, len: 1 },
+            ),
+            ("MZX", CodecError::TruncatedQuantum { offset: 0, len: 3 }),
+            ("MZXQ==", CodecError::TruncatedQuantum { offset: 0, len: 6 }),
+        ];
+        for case in cases {
+            assert_eq!(factory().try_to_plain(case.0.as_bytes()), Err(case.1));
+        }
+    }
+
+    #[test]
+    fn test_hex_to_b32() {
+        let hex = "666f6f626172";
+        let res = factory().encode_to_string(&Hexadecimal {}.to_plain(hex.as_bytes()));
+
+        assert_eq!(res, "MZXW6YTBOI======");
+        assert_eq!(
+            Hexadecimal {}.encode_to_string(&factory().to_plain(res.as_bytes())),
+            hex
+        );
+    }
+
+    #[test]
+    fn test_brute() {
+        let crypt_text = "Cooking MC's like a pound of bacon"
+            .bytes()
+            .map(|c| c ^ 0x58)
+            .collect::<Vec<u8>>();
+
+        let res = crate::crack::xor::brute(
+            &factory(),
+            &factory().encode_to_string(&crypt_text),
+            &crate::crack::score::AsciiScorer {},
+        );
+
+        assert_eq!(res.get_key(), factory().encode(&[0x58]));
+        assert_eq!(
+            res.get_decrypted_result(),
+            "Cooking MC's like a pound of bacon".as_bytes()
+        );
+    }
+}
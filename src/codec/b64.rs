@@ -1,10 +1,14 @@
 use super::adapter::{CodePointMap, Codec, CodecAPI};
+use super::error::CodecError;
 
 const UPPERCASEOFFSET: i8 = b'A' as i8; // b'A' is 65 in utf-8, but 0 in Base64. So the offset is b'A'-0.
 const LOWERCASEOFFSET: i8 = b'a' as i8 - 26; // b'a' is 97 in utf-8, but represents 26 in Base64. So the offset is b'a'-26=71.
 const DIGITOFFSET: i8 = b'0' as i8 - 52; // b'0' is 48 in utf-8, and represents 0 in Base64 (haha). So the offset is b'0'-52=-4
 const PADDING: i8 = '=' as i8;
 
+/// Marks a byte that is not part of an [Alphabet] in its reverse lookup table.
+const NOT_IN_ALPHABET: u8 = 0xff;
+
 #[derive(Copy, Clone)]
 pub struct Base64;
 impl CodePointMap for Base64 {
@@ -15,7 +19,7 @@ impl CodePointMap for Base64 {
             26..=51 => v + LOWERCASEOFFSET,
             52..=61 => v + DIGITOFFSET,
             62 => 43, // +
-            63 => 47, // -
+            63 => 47, // /
 
             _ => return None,
             /* We MUST not map any other characters outside the Base64 space.
@@ -38,7 +42,7 @@ impl CodePointMap for Base64 {
         let c = c as i8;
         let base64_index = match c {
             65..=90 => c - UPPERCASEOFFSET,
-            97..=127 => c - LOWERCASEOFFSET,
+            97..=122 => c - LOWERCASEOFFSET,
             48..=57 => c - DIGITOFFSET,
             43 => 62, // '+'
             47 => 63, // '/'
@@ -54,55 +58,19 @@ impl CodecAPI for Base64 {
     /// Bitwise operations to expand data in 3-byte chunks operating
     /// in an 8-bit space to 4-byte chunks operating in a 6-bit space.
     fn raw_encode(&self, chunk: &[u8]) -> Vec<u8> {
-        let mut res = match chunk.len() {
-            1 => vec![(&chunk[0] & 0b11111100) >> 2, (&chunk[0] & 0b00000011) << 4],
-            2 => vec![
-                (&chunk[0] & 0b11111100) >> 2,
-                (&chunk[0] & 0b00000011) << 4 | (&chunk[1] & 0b11110000) >> 4,
-                (&chunk[1] & 0b00001111) << 2,
-            ],
-            3 => vec![
-                (&chunk[0] & 0b11111100) >> 2,
-                (&chunk[0] & 0b00000011) << 4 | (&chunk[1] & 0b11110000) >> 4,
-                (&chunk[1] & 0b00001111) << 2 | (&chunk[2] & 0b11000000) >> 6,
-                &chunk[2] & 0b00111111,
-            ],
-            _ => unreachable!(),
-        } // after performing bitwise operations, map each resulting byte from u8 to base64 characters
-        .iter()
-        .filter_map(|c| self.map_codepoint_to_plain(*c))
-        .collect::<Vec<u8>>();
-
-        while res.len() < 4 {
-            res.push(PADDING as u8);
-        } // Inelegant, but we MUST pad only after mapping values to base64 chars
-          // because the padding characters must lie outside the mapped space.
-
-        res
+        encode_quantum(self, chunk, true)
     }
 
+    /// Bitwise operations to collapse a quantum of 2 to 4 code points operating
+    /// in a 6-bit space to 1 to 3 bytes operating in an 8-bit space.
+    ///
+    /// The number of code points (i.e. how much padding was stripped from the quantum)
+    /// decides how many bytes are produced, so decoded `0x00` bytes are preserved.
+    /// A quantum with fewer than 2 code points cannot hold a whole byte, and produces nothing.
     fn raw_to_plain(&self, chunk: &[u8]) -> Vec<u8> {
-        match chunk.len() {
-            2 => vec![
-                (&chunk[0] & 0b00111111) << 2 | (&chunk[1] & 0b11110000) >> 4,
-                (&chunk[1] & 0b00001111) << 4,
-            ],
-            3 => vec![
-                (&chunk[0] & 0b00111111) << 2 | (&chunk[1] & 0b00110000) >> 4,
-                (&chunk[1] & 0b00001111) << 4 | (&chunk[2] & 0b00111100) >> 2,
-                (&chunk[2] & 0b00000011) << 6,
-            ],
-            4 => vec![
-                (&chunk[0] & 0b00111111) << 2 | (&chunk[1] & 0b00110000) >> 4,
-                (&chunk[1] & 0b00001111) << 4 | (&chunk[2] & 0b00111100) >> 2,
-                (&chunk[2] & 0b00000011) << 6 | (&chunk[3] & 0b00111111),
-            ],
-            _ => unreachable!(),
-        }
-        .into_iter()
-        .filter(|c| *c > 0) // strip empty chunks post-decocde (EOL chars)
-        .collect()
+        decode_codepoints(chunk)
     }
+
     /// Set expected length of byte chunks to 3.
     /// See [crate::codec::adapter::CodecAPI::get_chunksize].
     fn get_chunksize(&self) -> usize {
@@ -113,27 +81,284 @@ impl CodecAPI for Base64 {
     /// because Base64 requires a different sequence of operations over
     /// the processed byte chunks.
     fn to_plain(&self, data: &[u8]) -> Vec<u8> {
-        data.chunks(4)
-            .map(|c| {
-                // retain chunk size by stripping padding and remapping
-                // within a map
-                c.iter()
-                    .filter(|c| **c != PADDING as u8)
-                    .filter_map(|c| self.map_plain_to_codepoint(*c))
-                    .collect::<Vec<u8>>()
-            })
-            .map(|c| self.raw_to_plain(&c))
-            .flatten()
-            .collect::<Vec<u8>>()
+        to_plain_lossy(self, data)
+    }
+
+    /// Decode Base64 strictly, one 4-character quantum at a time.
+    ///
+    /// The final quantum may omit its padding,
+    /// but it MUST still contain enough characters to decode at least one byte.
+    fn try_to_plain(&self, data: &[u8]) -> Result<Vec<u8>, CodecError> {
+        try_decode(self, data, true)
     }
 }
 
 impl Codec for Base64 {}
 
+/// The 64 characters a [Base64Engine] maps 6-bit code points to, in code point order.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Alphabet {
+    symbols: [u8; 64],
+    codepoints: [u8; 256],
+}
+
+impl Alphabet {
+    /// The standard alphabet, per [RFC 4648 §4](https://www.rfc-editor.org/rfc/rfc4648#section-4).
+    pub const STANDARD: Alphabet =
+        Alphabet::from_symbols(b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/");
+
+    /// The URL and filename safe alphabet, per [RFC 4648 §5](https://www.rfc-editor.org/rfc/rfc4648#section-5).
+    pub const URL_SAFE: Alphabet =
+        Alphabet::from_symbols(b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_");
+
+    /// Build an alphabet from 64 user-supplied characters.
+    ///
+    /// Every character MUST be printable ASCII, MUST be distinct,
+    /// and MUST NOT be the padding character `=`.
+    /// Otherwise, returns [CodecError::InvalidAlphabet] with the position of the first offending character.
+    pub fn new(symbols: &[u8]) -> Result<Alphabet, CodecError> {
+        for (offset, c) in symbols.iter().enumerate() {
+            if offset >= 64
+                || !c.is_ascii_graphic()
+                || *c == PADDING as u8
+                || symbols[..offset].contains(c)
+            {
+                return Err(CodecError::InvalidAlphabet { offset });
+            }
+        }
+        match <&[u8; 64]>::try_from(symbols) {
+            Ok(symbols) => Ok(Alphabet::from_symbols(symbols)),
+            Err(_) => Err(CodecError::InvalidAlphabet {
+                offset: symbols.len(),
+            }),
+        }
+    }
+
+    /// Build an alphabet and its reverse lookup table without validating `symbols`.
+    const fn from_symbols(symbols: &[u8; 64]) -> Alphabet {
+        let mut codepoints = [NOT_IN_ALPHABET; 256];
+        let mut i = 0;
+        while i < 64 {
+            codepoints[symbols[i] as usize] = i as u8;
+            i += 1;
+        }
+        Alphabet {
+            symbols: *symbols,
+            codepoints,
+        }
+    }
+}
+
+/// A configurable Base64 codec.
+///
+/// [Base64] always uses the standard alphabet and always pads its output.
+/// Use this instead for the URL-safe alphabet, for unpadded output,
+/// or for an arbitrary [Alphabet].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Base64Engine {
+    alphabet: Alphabet,
+    padding: bool,
+}
+
+impl Base64Engine {
+    /// A padded engine using `alphabet`.
+    pub fn new(alphabet: Alphabet) -> Base64Engine {
+        Base64Engine {
+            alphabet,
+            padding: true,
+        }
+    }
+
+    /// A padded engine using [Alphabet::STANDARD]. Behaves like [Base64].
+    pub fn standard() -> Base64Engine {
+        Base64Engine::new(Alphabet::STANDARD)
+    }
+
+    /// A padded engine using [Alphabet::URL_SAFE].
+    pub fn url_safe() -> Base64Engine {
+        Base64Engine::new(Alphabet::URL_SAFE)
+    }
+
+    /// Set whether encoded output is padded with `=`.
+    ///
+    /// An engine without padding rejects padded input in [CodecAPI::try_to_plain].
+    pub fn with_padding(self, padding: bool) -> Base64Engine {
+        Base64Engine { padding, ..self }
+    }
+}
+
+impl CodePointMap for Base64Engine {
+    fn map_codepoint_to_plain(&self, v: u8) -> Option<u8> {
+        self.alphabet.symbols.get(v as usize).copied()
+    }
+
+    fn map_plain_to_codepoint(&self, c: u8) -> Option<u8> {
+        match self.alphabet.codepoints[c as usize] {
+            NOT_IN_ALPHABET => None,
+            v => Some(v),
+        }
+    }
+}
+
+impl CodecAPI for Base64Engine {
+    /// See [Base64::raw_encode].
+    fn raw_encode(&self, chunk: &[u8]) -> Vec<u8> {
+        encode_quantum(self, chunk, self.padding)
+    }
+
+    /// See [Base64::raw_to_plain].
+    fn raw_to_plain(&self, chunk: &[u8]) -> Vec<u8> {
+        decode_codepoints(chunk)
+    }
+
+    fn get_chunksize(&self) -> usize {
+        3
+    }
+
+    fn to_plain(&self, data: &[u8]) -> Vec<u8> {
+        to_plain_lossy(self, data)
+    }
+
+    fn try_to_plain(&self, data: &[u8]) -> Result<Vec<u8>, CodecError> {
+        try_decode(self, data, self.padding)
+    }
+}
+
+impl Codec for Base64Engine {}
+
+/// Expand up to 3 bytes into a quantum of Base64 characters from the alphabet of `map`.
+fn encode_quantum<M: CodePointMap>(map: &M, chunk: &[u8], padding: bool) -> Vec<u8> {
+    let mut res = match chunk.len() {
+        1 => vec![(&chunk[0] & 0b11111100) >> 2, (&chunk[0] & 0b00000011) << 4],
+        2 => vec![
+            (&chunk[0] & 0b11111100) >> 2,
+            (&chunk[0] & 0b00000011) << 4 | (&chunk[1] & 0b11110000) >> 4,
+            (&chunk[1] & 0b00001111) << 2,
+        ],
+        3 => vec![
+            (&chunk[0] & 0b11111100) >> 2,
+            (&chunk[0] & 0b00000011) << 4 | (&chunk[1] & 0b11110000) >> 4,
+            (&chunk[1] & 0b00001111) << 2 | (&chunk[2] & 0b11000000) >> 6,
+            &chunk[2] & 0b00111111,
+        ],
+        _ => unreachable!(),
+    } // after performing bitwise operations, map each resulting byte from u8 to base64 characters
+    .iter()
+    .filter_map(|c| map.map_codepoint_to_plain(*c))
+    .collect::<Vec<u8>>();
+
+    while padding && res.len() < 4 {
+        res.push(PADDING as u8);
+    } // Inelegant, but we MUST pad only after mapping values to base64 chars
+      // because the padding characters must lie outside the mapped space.
+
+    res
+}
+
+/// See [Base64::raw_to_plain].
+fn decode_codepoints(chunk: &[u8]) -> Vec<u8> {
+    match chunk.len() {
+        0 | 1 => vec![],
+        2 => vec![(&chunk[0] & 0b00111111) << 2 | (&chunk[1] & 0b00110000) >> 4],
+        3 => vec![
+            (&chunk[0] & 0b00111111) << 2 | (&chunk[1] & 0b00110000) >> 4,
+            (&chunk[1] & 0b00001111) << 4 | (&chunk[2] & 0b00111100) >> 2,
+        ],
+        4 => vec![
+            (&chunk[0] & 0b00111111) << 2 | (&chunk[1] & 0b00110000) >> 4,
+            (&chunk[1] & 0b00001111) << 4 | (&chunk[2] & 0b00111100) >> 2,
+            (&chunk[2] & 0b00000011) << 6 | (&chunk[3] & 0b00111111),
+        ],
+        _ => unreachable!(),
+    }
+}
+
+/// See [Base64::to_plain].
+fn to_plain_lossy<M: CodePointMap>(map: &M, data: &[u8]) -> Vec<u8> {
+    data.chunks(4)
+        .map(|c| {
+            // retain chunk size by stripping padding and remapping
+            // within a map
+            c.iter()
+                .filter(|c| **c != PADDING as u8)
+                .filter_map(|c| map.map_plain_to_codepoint(*c))
+                .collect::<Vec<u8>>()
+        })
+        .flat_map(|c| decode_codepoints(&c))
+        .collect::<Vec<u8>>()
+}
+
+/// See [Base64::try_to_plain].
+///
+/// If `padding` is false, any padding character is rejected.
+fn try_decode<M: CodePointMap>(map: &M, data: &[u8], padding: bool) -> Result<Vec<u8>, CodecError> {
+    let mut res: Vec<u8> = Vec::with_capacity(data.len() / 4 * 3);
+    for (i, quantum) in data.chunks(4).enumerate() {
+        let offset = i * 4;
+        let is_last = padding && offset + quantum.len() == data.len();
+        let codepoints = try_map_quantum(map, quantum, offset, is_last)?;
+        res.extend(decode_codepoints(&codepoints));
+    }
+    Ok(res)
+}
+
+/// Map a single quantum of Base64 characters to 6-bit code points,
+/// validating the alphabet and the position of any padding.
+///
+/// `offset` is the position of `quantum` in the whole input, used for error reporting.
+/// Padding is only accepted if `may_pad` is set.
+fn try_map_quantum<M: CodePointMap>(
+    map: &M,
+    quantum: &[u8],
+    offset: usize,
+    may_pad: bool,
+) -> Result<Vec<u8>, CodecError> {
+    let mut codepoints: Vec<u8> = Vec::with_capacity(4);
+    for (i, c) in quantum.iter().enumerate() {
+        if *c == PADDING as u8 {
+            // Padding may only fill the 3rd and 4th positions of the final, complete quantum.
+            if !may_pad || i < 2 {
+                return Err(CodecError::InvalidPadding { offset: offset + i });
+            }
+            if quantum.len() < 4 {
+                return Err(CodecError::TruncatedQuantum {
+                    offset,
+                    len: quantum.len(),
+                });
+            }
+            continue;
+        }
+        if codepoints.len() < i {
+            // A data character follows padding, so the padding was misplaced.
+            return Err(CodecError::InvalidPadding {
+                offset: offset + codepoints.len(),
+            });
+        }
+        match map.map_plain_to_codepoint(*c) {
+            Some(v) => codepoints.push(v),
+            None => {
+                return Err(CodecError::InvalidCharacter {
+                    offset: offset + i,
+                    byte: *c,
+                })
+            }
+        }
+    }
+
+    if codepoints.len() < 2 {
+        return Err(CodecError::TruncatedQuantum {
+            offset,
+            len: codepoints.len(),
+        });
+    }
+    Ok(codepoints)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Base64;
+    use super::{Alphabet, Base64, Base64Engine};
     use crate::codec::adapter::CodecAPI;
+    use crate::codec::error::CodecError;
 
     fn factory() -> Base64 {
         Base64 {}
@@ -238,4 +463,218 @@ mod tests {
 
         assert_eq!(factory().to_plain_string(input_data), expected);
     }
+
+    #[test]
+    fn test_decode_preserves_zero_bytes() {
+        let cases: [(&str, &[u8]); 4] = [
+            ("AA==", &[0]),
+            ("AAA=", &[0, 0]),
+            ("AAAA", &[0, 0, 0]),
+            ("YQBi", &[b'a', 0, b'b']),
+        ];
+        for case in cases {
+            assert_eq!(factory().to_plain(case.0.as_bytes()), case.1);
+            assert_eq!(
+                factory().try_to_plain(case.0.as_bytes()),
+                Ok(case.1.to_vec())
+            );
+        }
+    }
+
+    #[test]
+    fn test_try_decode() {
+        let cases = [
+            ("", ""),
+            ("YQ==", "a"),
+            ("YWI=", "ab"),
+            ("YWJj", "abc"),
+            ("YQ", "a"),
+            ("YWI", "ab"),
+            ("SGVsbG8sIHdvcmxkIQ==", "Hello, world!"),
+        ];
+        for case in cases {
+            assert_eq!(
+                factory().try_to_plain_string(case.0.as_bytes()),
+                Ok(case.1.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn test_try_decode_invalid_character() {
+        let cases = [
+            (
+                "YW{j",
+                CodecError::InvalidCharacter {
+                    offset: 2,
+                    byte: b'{',
+                },
+            ),
+            (
+                "YWJjYW-j",
+                CodecError::InvalidCharacter {
+                    offset: 6,
+                    byte: b'-',
+                },
+            ),
+            (
+                "YWJj\nYWJj",
+                CodecError::InvalidCharacter {
+                    offset: 4,
+                    byte: b'\n',
+                },
+            ),
+            (
+                "YW\u{ff}j",
+                CodecError::InvalidCharacter {
+                    offset: 2,
+                    byte: 0xc3,
+                },
+            ),
+        ];
+        for case in cases {
+            assert_eq!(factory().try_to_plain(case.0.as_bytes()), Err(case.1));
+        }
+    }
+
+    #[test]
+    fn test_try_decode_invalid_padding() {
+        let cases = [
+            ("YQ==YWJj", CodecError::InvalidPadding { offset: 2 }),
+            ("Y===", CodecError::InvalidPadding { offset: 1 }),
+            ("====", CodecError::InvalidPadding { offset: 0 }),
+            ("YQ=j", CodecError::InvalidPadding { offset: 2 }),
+        ];
+        for case in cases {
+            assert_eq!(factory().try_to_plain(case.0.as_bytes()), Err(case.1));
+        }
+    }
+
+    #[test]
+    fn test_try_decode_truncated_quantum() {
+        let cases = [
+            ("YWJjY", CodecError::TruncatedQuantum { offset: 4, len: 1 }),
+            ("YQ=", CodecError::TruncatedQuantum { offset: 0, len: 3 }),
+        ];
+        for case in cases {
+            assert_eq!(factory().try_to_plain(case.0.as_bytes()), Err(case.1));
+        }
+    }
+
+    #[test]
+    fn test_engine_standard_matches_base64() {
+        let input = b"And here be a bit longer text. Let's see how it goes!\xff\xfe";
+        let engine = Base64Engine::standard();
+
+        assert_eq!(engine.encode(input), factory().encode(input));
+        assert_eq!(
+            engine.try_to_plain(&factory().encode(input)),
+            Ok(input.to_vec())
+        );
+    }
+
+    #[test]
+    fn test_engine_url_safe() {
+        let input: &[u8] = &[0xfb, 0xff, 0xbf, 0x3e];
+        let engine = Base64Engine::url_safe();
+
+        assert_eq!(factory().encode_to_string(input), "+/+/Pg==");
+        assert_eq!(engine.encode_to_string(input), "-_-_Pg==");
+        assert_eq!(
+            engine.try_to_plain("-_-_Pg==".as_bytes()),
+            Ok(input.to_vec())
+        );
+        assert_eq!(
+            engine.try_to_plain("+/+/Pg==".as_bytes()),
+            Err(CodecError::InvalidCharacter {
+                offset: 0,
+                byte: b'+'
+            })
+        );
+    }
+
+    #[test]
+    fn test_engine_no_padding() {
+        let engine = Base64Engine::url_safe().with_padding(false);
+        let cases = [
+            ("a", "YQ"),
+            ("ab", "YWI"),
+            ("abc", "YWJj"),
+            ("abcd", "YWJjZA"),
+        ];
+        for case in cases {
+            assert_eq!(engine.encode_to_string(case.0.as_bytes()), case.1);
+            assert_eq!(
+                engine.try_to_plain_string(case.1.as_bytes()),
+                Ok(case.0.to_string())
+            );
+        }
+        assert_eq!(
+            engine.try_to_plain("YQ==".as_bytes()),
+            Err(CodecError::InvalidPadding { offset: 2 })
+        );
+    }
+
+    #[test]
+    fn test_engine_custom_alphabet() {
+        // Standard alphabet, rotated by one character.
+        let alphabet =
+            Alphabet::new(b"BCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/A")
+                .unwrap();
+        let engine = Base64Engine::new(alphabet);
+
+        assert_eq!(engine.encode_to_string("abc".as_bytes()), "ZXKk");
+        assert_eq!(
+            engine.try_to_plain_string("ZXKk".as_bytes()),
+            Ok("abc".to_string())
+        );
+    }
+
+    #[test]
+    fn test_alphabet_invalid() {
+        let cases: [(&[u8], CodecError); 5] = [
+            (b"ABC", CodecError::InvalidAlphabet { offset: 3 }),
+            (
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/!",
+                CodecError::InvalidAlphabet { offset: 64 },
+            ),
+            (
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+A",
+                CodecError::InvalidAlphabet { offset: 63 },
+            ),
+            (
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+=",
+                CodecError::InvalidAlphabet { offset: 63 },
+            ),
+            (
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz012345678 +/",
+                CodecError::InvalidAlphabet { offset: 61 },
+            ),
+        ];
+        for case in cases {
+            assert_eq!(Alphabet::new(case.0), Err(case.1));
+        }
+    }
+
+    #[test]
+    fn test_engine_brute() {
+        let engine = Base64Engine::url_safe().with_padding(false);
+        let key = engine.encode(&[0x58]);
+        let crypt_text = "Cooking MC's like a pound of bacon"
+            .bytes()
+            .map(|c| c ^ 0x58)
+            .collect::<Vec<u8>>();
+
+        let res = crate::crack::xor::brute(
+            &engine,
+            &engine.encode_to_string(&crypt_text),
+            &crate::crack::score::AsciiScorer {},
+        );
+
+        assert_eq!(res.get_key(), key);
+        assert_eq!(
+            res.get_decrypted_result(),
+            "Cooking MC's like a pound of bacon".as_bytes()
+        );
+    }
 }
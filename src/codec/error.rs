@@ -0,0 +1,84 @@
+//! Errors returned by the fallible `try_*` family of [crate::codec::adapter::CodecAPI] methods.
+
+use std::fmt;
+
+/// Describes why a byte slice could not be decoded by a [crate::codec::adapter::Codec].
+///
+/// Offsets are zero-based positions in the encoded input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CodecError {
+    /// `byte` at `offset` is not part of the codec's alphabet.
+    InvalidCharacter { offset: usize, byte: u8 },
+
+    /// Hexadecimal input must contain an even number of digits,
+    /// because every byte is represented by exactly two of them.
+    OddLength { len: usize },
+
+    /// A padding character was found at `offset`,
+    /// where padding is not allowed.
+    InvalidPadding { offset: usize },
+
+    /// The final quantum starting at `offset` only has `len` characters,
+    /// which is not enough to decode a whole byte.
+    TruncatedQuantum { offset: usize, len: usize },
+
+    /// A custom alphabet is invalid from `offset` onwards, because the character there
+    /// is missing, surplus, repeated, or not allowed in an alphabet.
+    InvalidAlphabet { offset: usize },
+}
+
+impl CodecError {
+    /// Shift the offsets in this error by `base`,
+    /// for errors found in a slice that starts `base` bytes into the whole input.
+    pub(crate) fn offset_by(self, base: usize) -> CodecError {
+        match self {
+            CodecError::OddLength { len } => CodecError::OddLength { len: len + base },
+            _ => self.map_offset(|offset| offset + base),
+        }
+    }
+
+    /// Translate the offset in this error with `f`,
+    /// for errors found in a transformed copy of the whole input.
+    pub(crate) fn map_offset<F: Fn(usize) -> usize>(self, f: F) -> CodecError {
+        match self {
+            CodecError::InvalidCharacter { offset, byte } => CodecError::InvalidCharacter {
+                offset: f(offset),
+                byte,
+            },
+            CodecError::InvalidPadding { offset } => {
+                CodecError::InvalidPadding { offset: f(offset) }
+            }
+            CodecError::TruncatedQuantum { offset, len } => CodecError::TruncatedQuantum {
+                offset: f(offset),
+                len,
+            },
+            CodecError::OddLength { .. } | CodecError::InvalidAlphabet { .. } => self,
+        }
+    }
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodecError::InvalidCharacter { offset, byte } => {
+                write!(f, "invalid character {:#04x} at offset {}", byte, offset)
+            }
+            CodecError::OddLength { len } => {
+                write!(f, "input length {} is not a multiple of 2", len)
+            }
+            CodecError::InvalidPadding { offset } => {
+                write!(f, "unexpected padding at offset {}", offset)
+            }
+            CodecError::TruncatedQuantum { offset, len } => write!(
+                f,
+                "truncated final quantum of {} character(s) at offset {}",
+                len, offset
+            ),
+            CodecError::InvalidAlphabet { offset } => {
+                write!(f, "invalid alphabet character at offset {}", offset)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
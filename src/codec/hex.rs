@@ -1,4 +1,5 @@
 use super::adapter::{CodePointMap, Codec, CodecAPI};
+use super::error::CodecError;
 
 #[derive(Copy, Clone)]
 pub struct Hexadecimal;
@@ -53,6 +54,31 @@ impl CodecAPI for Hexadecimal {
     fn get_chunksize(&self) -> usize {
         2
     }
+
+    /// Every byte is 2 hex digits, so a chunk of 2 bytes is 4 characters long.
+    /// See [crate::codec::adapter::CodecAPI::get_encoded_chunksize].
+    fn get_encoded_chunksize(&self) -> usize {
+        4
+    }
+
+    /// Decode hexadecimal strictly:
+    /// every character must be a hex digit, and there must be an even number of them.
+    fn try_to_plain(&self, data: &[u8]) -> Result<Vec<u8>, CodecError> {
+        let nibbles = data
+            .iter()
+            .enumerate()
+            .map(|(offset, c)| match self.map_plain_to_codepoint(*c) {
+                Some(v) if v < 16 => Ok(v),
+                _ => Err(CodecError::InvalidCharacter { offset, byte: *c }),
+            })
+            .collect::<Result<Vec<u8>, CodecError>>()?;
+
+        if nibbles.len() % 2 != 0 {
+            return Err(CodecError::OddLength { len: data.len() });
+        }
+
+        Ok(nibbles.chunks(2).map(|c| c[0] << 4 | c[1]).collect())
+    }
 }
 
 impl Codec for Hexadecimal {}
@@ -61,6 +87,7 @@ impl Codec for Hexadecimal {}
 mod tests {
     use super::Hexadecimal;
     use crate::codec::adapter::CodecAPI;
+    use crate::codec::error::CodecError;
 
     fn factory() -> Hexadecimal {
         Hexadecimal {}
@@ -165,4 +192,60 @@ mod tests {
 
         assert_eq!(factory().to_plain_string(input_data), expected);
     }
+
+    #[test]
+    fn test_try_decode() {
+        assert_eq!(
+            factory().try_to_plain_string("48656C6c6f".as_bytes()),
+            Ok("Hello".to_string())
+        );
+        assert_eq!(factory().try_to_plain("".as_bytes()), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_try_decode_invalid_character() {
+        let cases = [
+            (
+                "61g2",
+                CodecError::InvalidCharacter {
+                    offset: 2,
+                    byte: b'g',
+                },
+            ),
+            (
+                "6162\n",
+                CodecError::InvalidCharacter {
+                    offset: 4,
+                    byte: b'\n',
+                },
+            ),
+            (
+                "z",
+                CodecError::InvalidCharacter {
+                    offset: 0,
+                    byte: b'z',
+                },
+            ),
+        ];
+        for case in cases {
+            assert_eq!(factory().try_to_plain(case.0.as_bytes()), Err(case.1));
+        }
+    }
+
+    #[test]
+    fn test_chunksizes() {
+        let chunk = vec![0xab; factory().get_chunksize()];
+        assert_eq!(
+            factory().encode(&chunk).len(),
+            factory().get_encoded_chunksize()
+        );
+    }
+
+    #[test]
+    fn test_try_decode_odd_length() {
+        assert_eq!(
+            factory().try_to_plain("616".as_bytes()),
+            Err(CodecError::OddLength { len: 3 })
+        );
+    }
 }
@@ -0,0 +1,94 @@
+use super::adapter::{CodePointMap, Codec, CodecAPI};
+use super::error::CodecError;
+
+/// Identity codec, for content that is already plain bytes.
+///
+/// Encoding and decoding return the content unchanged, and decoding never fails.
+/// Lets functions that operate through a [Codec] work on binary content,
+/// e.g. ciphertext read from a binary file or a socket.
+#[derive(Copy, Clone)]
+pub struct Raw;
+
+impl CodePointMap for Raw {
+    fn map_codepoint_to_plain(&self, v: u8) -> Option<u8> {
+        Some(v)
+    }
+
+    fn map_plain_to_codepoint(&self, c: u8) -> Option<u8> {
+        Some(c)
+    }
+}
+
+impl CodecAPI for Raw {
+    fn raw_encode(&self, v: &[u8]) -> Vec<u8> {
+        v.to_vec()
+    }
+
+    fn raw_to_plain(&self, v: &[u8]) -> Vec<u8> {
+        v.to_vec()
+    }
+
+    /// Every byte stands on its own, so there is no need to chunk content.
+    fn get_chunksize(&self) -> usize {
+        1
+    }
+
+    fn get_encoded_chunksize(&self) -> usize {
+        1
+    }
+
+    /// Raw content is binary, so it MUST NOT be split into lines.
+    fn is_textual(&self) -> bool {
+        false
+    }
+
+    fn encode(&self, data: &[u8]) -> Vec<u8> {
+        data.to_vec()
+    }
+
+    fn to_plain(&self, data: &[u8]) -> Vec<u8> {
+        data.to_vec()
+    }
+
+    fn try_to_plain(&self, data: &[u8]) -> Result<Vec<u8>, CodecError> {
+        Ok(data.to_vec())
+    }
+}
+
+impl Codec for Raw {}
+
+#[cfg(test)]
+mod tests {
+    use super::Raw;
+    use crate::codec::adapter::CodecAPI;
+    use crate::codec::hex::Hexadecimal;
+    use crate::codec::transcode::transcode;
+
+    fn factory() -> Raw {
+        Raw {}
+    }
+
+    const BINARY: &[u8] = &[0x00, 0x0a, 0xff, 0x0d, 0x0a, 0x80, 0x7f, 0x0a];
+
+    #[test]
+    fn test_identity() {
+        assert_eq!(factory().encode(BINARY), BINARY);
+        assert_eq!(factory().to_plain(BINARY), BINARY);
+        assert_eq!(factory().try_to_plain(BINARY), Ok(BINARY.to_vec()));
+        assert_eq!(factory().try_encode(BINARY), Ok(BINARY.to_vec()));
+    }
+
+    #[test]
+    fn test_transcode() {
+        let hex = "000aff0d0a807f0a".as_bytes();
+
+        assert_eq!(
+            transcode(&factory(), &Hexadecimal {}, BINARY),
+            Ok(hex.to_vec())
+        );
+        assert_eq!(
+            transcode(&Hexadecimal {}, &factory(), hex),
+            Ok(BINARY.to_vec())
+        );
+    }
+}
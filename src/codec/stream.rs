@@ -0,0 +1,446 @@
+//! Streaming adapters that encode and decode through [std::io::Write] and [std::io::Read],
+//! so content never has to be held in memory as a whole.
+
+use super::adapter::Codec;
+use super::error::CodecError;
+use std::io::{self, Read, Write};
+
+/// Size of the reads [DecoderReader] makes from its inner reader.
+const READ_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Wrap a [CodecError] found in a stream as an [io::Error].
+fn invalid_data(e: CodecError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+/// Encodes everything written to it with `codec`, and writes the encoded content to `inner`.
+///
+/// Bytes that don't fill a whole chunk (see [crate::codec::adapter::CodecAPI::get_chunksize])
+/// are buffered across calls to `write`.
+/// Call [EncoderWriter::finish] to encode and pad the final, partial chunk.
+/// Dropping an unfinished writer also does this, but ignores any errors.
+pub struct EncoderWriter<W: Write, C: Codec> {
+    inner: Option<W>,
+    codec: C,
+    pending: Vec<u8>,
+    encoded: usize,
+}
+
+impl<W: Write, C: Codec> EncoderWriter<W, C> {
+    pub fn new(inner: W, codec: C) -> EncoderWriter<W, C> {
+        EncoderWriter {
+            inner: Some(inner),
+            codec,
+            pending: Vec::new(),
+            encoded: 0,
+        }
+    }
+
+    /// Encode any buffered bytes as the final chunk, flush, and return the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_final()?;
+        Ok(self
+            .inner
+            .take()
+            .expect("inner writer is only taken on finish"))
+    }
+
+    fn write_final(&mut self) -> io::Result<()> {
+        if let Some(inner) = self.inner.as_mut() {
+            if !self.pending.is_empty() {
+                let encoded = self
+                    .codec
+                    .try_encode_at(&self.pending, self.encoded)
+                    .map_err(invalid_data)?;
+                self.encoded += self.pending.len();
+                self.pending.clear();
+                inner.write_all(&encoded)?;
+            }
+            inner.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write, C: Codec> Write for EncoderWriter<W, C> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let inner = match self.inner.as_mut() {
+            Some(inner) => inner,
+            None => return Err(io::Error::other("writer is already finished")),
+        };
+        self.pending.extend_from_slice(buf);
+
+        let chunksize = self.codec.get_chunksize();
+        let whole = self.pending.len() / chunksize * chunksize;
+        if whole > 0 {
+            let encoded = self
+                .codec
+                .try_encode_at(&self.pending[..whole], self.encoded)
+                .map_err(invalid_data)?;
+            inner.write_all(&encoded)?;
+            self.pending.drain(..whole);
+            self.encoded += whole;
+        }
+        Ok(buf.len())
+    }
+
+    /// Flush the inner writer.
+    /// Doesn't encode a partial chunk, because that would pad the middle of the stream.
+    fn flush(&mut self) -> io::Result<()> {
+        match self.inner.as_mut() {
+            Some(inner) => inner.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<W: Write, C: Codec> Drop for EncoderWriter<W, C> {
+    fn drop(&mut self) {
+        let _ = self.write_final();
+    }
+}
+
+/// Reads plain content from `inner`, and encodes it with `codec`.
+///
+/// The read-side counterpart to [EncoderWriter],
+/// for when encoded content has to be pulled rather than pushed.
+pub struct EncoderReader<R: Read, C: Codec> {
+    inner: R,
+    codec: C,
+    pending: Vec<u8>,
+    encoded: Vec<u8>,
+    pos: usize,
+    consumed: usize,
+    eof: bool,
+}
+
+impl<R: Read, C: Codec> EncoderReader<R, C> {
+    pub fn new(inner: R, codec: C) -> EncoderReader<R, C> {
+        EncoderReader {
+            inner,
+            codec,
+            pending: Vec::new(),
+            encoded: Vec::new(),
+            pos: 0,
+            consumed: 0,
+            eof: false,
+        }
+    }
+
+    /// Return the inner reader. Any buffered content is lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Read more plain content, and encode all the whole chunks in it.
+    /// At the end of the stream, encode whatever is left as the final chunk.
+    fn fill(&mut self) -> io::Result<()> {
+        let mut buf = [0u8; READ_BUFFER_SIZE];
+        match self.inner.read(&mut buf) {
+            Ok(0) => self.eof = true,
+            Ok(n) => self.pending.extend_from_slice(&buf[..n]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => return Ok(()),
+            Err(e) => return Err(e),
+        }
+
+        let chunksize = self.codec.get_chunksize();
+        let ready = match self.eof {
+            true => self.pending.len(),
+            false => self.pending.len() / chunksize * chunksize,
+        };
+        self.encoded = self
+            .codec
+            .try_encode_at(&self.pending[..ready], self.consumed)
+            .map_err(invalid_data)?;
+        self.pos = 0;
+        self.pending.drain(..ready);
+        self.consumed += ready;
+        Ok(())
+    }
+}
+
+impl<R: Read, C: Codec> Read for EncoderReader<R, C> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        while self.pos == self.encoded.len() {
+            if self.eof {
+                return Ok(0);
+            }
+            self.fill()?;
+        }
+
+        let n = buf.len().min(self.encoded.len() - self.pos);
+        buf[..n].copy_from_slice(&self.encoded[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Reads encoded content from `inner`, and decodes it with `codec`.
+///
+/// Decoding is strict, as with [crate::codec::adapter::CodecAPI::try_to_plain].
+/// Malformed content fails the read with [io::ErrorKind::InvalidData],
+/// wrapping a [CodecError] with offsets counted from the start of the stream.
+pub struct DecoderReader<R: Read, C: Codec> {
+    inner: R,
+    codec: C,
+    encoded: Vec<u8>,
+    decoded: Vec<u8>,
+    pos: usize,
+    consumed: usize,
+    eof: bool,
+}
+
+impl<R: Read, C: Codec> DecoderReader<R, C> {
+    pub fn new(inner: R, codec: C) -> DecoderReader<R, C> {
+        DecoderReader {
+            inner,
+            codec,
+            encoded: Vec::new(),
+            decoded: Vec::new(),
+            pos: 0,
+            consumed: 0,
+            eof: false,
+        }
+    }
+
+    /// Return the inner reader. Any buffered content is lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Find the end of the last whole quantum in the buffered encoded content
+    /// that is followed by at least one more byte of content.
+    ///
+    /// Returns that position, and the number of whole quanta before it.
+    fn whole_quanta_before_last(&self, quantum: usize) -> (usize, usize) {
+        let mut content = 0;
+        let mut res = (0, 0);
+        for (i, c) in self.encoded.iter().enumerate() {
+            if self.codec.skips(*c) {
+                continue;
+            }
+            if content > 0 && content % quantum == 0 {
+                res = (i, content / quantum);
+            }
+            content += 1;
+        }
+        res
+    }
+
+    /// Read more encoded content, and decode as much of it as can be decoded.
+    fn fill(&mut self) -> io::Result<()> {
+        if !self.eof {
+            let mut buf = [0u8; READ_BUFFER_SIZE];
+            match self.inner.read(&mut buf) {
+                Ok(0) => self.eof = true,
+                Ok(n) => self.encoded.extend_from_slice(&buf[..n]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => return Ok(()),
+                Err(e) => return Err(e),
+            }
+        }
+
+        // Until the end of the stream, hold back at least one byte of content,
+        // so that padding is only ever accepted in the real final quantum.
+        // Bytes the codec skips (e.g. line breaks) don't count towards a quantum.
+        let quantum = self.codec.get_encoded_chunksize();
+        let (ready, quanta) = if self.eof {
+            (self.encoded.len(), 0)
+        } else {
+            self.whole_quanta_before_last(quantum)
+        };
+        if ready == 0 {
+            return Ok(());
+        }
+
+        let decoded = self
+            .codec
+            .try_to_plain(&self.encoded[..ready])
+            .map_err(|e| invalid_data(e.offset_by(self.consumed)))?;
+        if !self.eof && decoded.len() != quanta * self.codec.get_chunksize() {
+            // A padded quantum was decoded as if it were the last one, but more content follows.
+            // Decode it along with the content that was held back, to find out what's wrong with it.
+            let e = self
+                .codec
+                .try_to_plain(&self.encoded)
+                .err()
+                .unwrap_or(CodecError::InvalidPadding { offset: ready });
+            return Err(invalid_data(e.offset_by(self.consumed)));
+        }
+        self.decoded = decoded;
+        self.pos = 0;
+        self.encoded.drain(..ready);
+        self.consumed += ready;
+        Ok(())
+    }
+}
+
+impl<R: Read, C: Codec> Read for DecoderReader<R, C> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        while self.pos == self.decoded.len() {
+            if self.eof && self.encoded.is_empty() {
+                return Ok(0);
+            }
+            self.fill()?;
+        }
+
+        let n = buf.len().min(self.decoded.len() - self.pos);
+        buf[..n].copy_from_slice(&self.decoded[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::adapter::CodecAPI;
+    use crate::codec::b32::Base32;
+    use crate::codec::b64::Base64;
+    use crate::codec::hex::Hexadecimal;
+
+    const PLAIN: &str = "And here be a bit longer text. Let's see how it goes!";
+
+    /// Reader that hands out at most `step` bytes per read, to exercise buffering.
+    struct Trickle<'a> {
+        data: &'a [u8],
+        step: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.step.min(buf.len()).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    fn encode_in_steps<C: Codec + Copy>(codec: C, data: &[u8], step: usize) -> Vec<u8> {
+        let mut writer = EncoderWriter::new(Vec::new(), codec);
+        for chunk in data.chunks(step) {
+            writer.write_all(chunk).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    fn decode_in_steps<C: Codec>(codec: C, data: &[u8], step: usize) -> io::Result<Vec<u8>> {
+        let mut res = Vec::new();
+        DecoderReader::new(Trickle { data, step }, codec).read_to_end(&mut res)?;
+        Ok(res)
+    }
+
+    #[test]
+    fn test_encoder_writer() {
+        for step in 1..=9 {
+            assert_eq!(
+                encode_in_steps(Base64 {}, PLAIN.as_bytes(), step),
+                Base64 {}.encode(PLAIN.as_bytes())
+            );
+            assert_eq!(
+                encode_in_steps(Base32 {}, PLAIN.as_bytes(), step),
+                Base32 {}.encode(PLAIN.as_bytes())
+            );
+            assert_eq!(
+                encode_in_steps(Hexadecimal {}, PLAIN.as_bytes(), step),
+                Hexadecimal {}.encode(PLAIN.as_bytes())
+            );
+        }
+    }
+
+    #[test]
+    fn test_encoder_writer_finishes_on_drop() {
+        let mut out: Vec<u8> = Vec::new();
+        {
+            let mut writer = EncoderWriter::new(&mut out, Base64 {});
+            writer.write_all("a".as_bytes()).unwrap();
+        }
+        assert_eq!(out, "YQ==".as_bytes());
+    }
+
+    #[test]
+    fn test_encoder_reader() {
+        for step in 1..=9 {
+            let mut res = Vec::new();
+            EncoderReader::new(
+                Trickle {
+                    data: PLAIN.as_bytes(),
+                    step,
+                },
+                Base32 {},
+            )
+            .read_to_end(&mut res)
+            .unwrap();
+            assert_eq!(res, Base32 {}.encode(PLAIN.as_bytes()));
+        }
+    }
+
+    #[test]
+    fn test_decoder_reader() {
+        for step in 1..=9 {
+            let encoded = Base64 {}.encode(PLAIN.as_bytes());
+            assert_eq!(
+                decode_in_steps(Base64 {}, &encoded, step).unwrap(),
+                PLAIN.as_bytes()
+            );
+            let encoded = Base32 {}.encode(PLAIN.as_bytes());
+            assert_eq!(
+                decode_in_steps(Base32 {}, &encoded, step).unwrap(),
+                PLAIN.as_bytes()
+            );
+            let encoded = Hexadecimal {}.encode(PLAIN.as_bytes());
+            assert_eq!(
+                decode_in_steps(Hexadecimal {}, &encoded, step).unwrap(),
+                PLAIN.as_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn test_round_trip_large() {
+        let data = (0..100_000u32)
+            .map(|i| (i.wrapping_mul(2654435761) >> 13) as u8)
+            .collect::<Vec<u8>>();
+        let encoded = encode_in_steps(Base64 {}, &data, 4093);
+
+        assert_eq!(encoded, Base64 {}.encode(&data));
+        assert_eq!(decode_in_steps(Base64 {}, &encoded, 1021).unwrap(), data);
+    }
+
+    #[test]
+    fn test_decoder_reader_errors() {
+        let cases: [(&str, CodecError); 3] = [
+            (
+                "YWJjYWJjYQ==YWJj",
+                CodecError::InvalidPadding { offset: 10 },
+            ),
+            (
+                "YWJjYWJjYW-j",
+                CodecError::InvalidCharacter {
+                    offset: 10,
+                    byte: b'-',
+                },
+            ),
+            (
+                "YWJjYWJjY",
+                CodecError::TruncatedQuantum { offset: 8, len: 1 },
+            ),
+        ];
+        for case in cases {
+            for step in [1, 3, 64] {
+                let err = decode_in_steps(Base64 {}, case.0.as_bytes(), step).unwrap_err();
+
+                assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+                assert_eq!(
+                    err.get_ref().and_then(|e| e.downcast_ref::<CodecError>()),
+                    Some(&case.1)
+                );
+            }
+        }
+    }
+}
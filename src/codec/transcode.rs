@@ -0,0 +1,232 @@
+//! Transcoding between any pair of codecs.
+//!
+//! [transcode] decodes content with one [Codec] and re-encodes it with another.
+//! [Transcoder] does the same as a reusable pipeline,
+//! which can be chained to peel off or add several layers of encoding,
+//! and run over streams as well as byte slices.
+
+use super::adapter::Codec;
+use super::error::CodecError;
+use super::stream::{DecoderReader, EncoderReader};
+use std::io::{self, Read, Write};
+
+/// Decode `data` with `from`, and encode the result with `to`.
+///
+/// For example, `transcode(&Hexadecimal {}, &Base64 {}, data)` converts hex to Base64.
+/// Use [crate::codec::raw::Raw] on either side to decode to, or encode from, plain bytes.
+pub fn transcode<F: Codec, T: Codec>(from: &F, to: &T, data: &[u8]) -> Result<Vec<u8>, CodecError> {
+    to.try_encode(&from.try_to_plain(data)?)
+}
+
+/// Convenience function that wraps [transcode]
+/// to return the transcoded content as a String.
+pub fn transcode_to_string<F: Codec, T: Codec>(
+    from: &F,
+    to: &T,
+    data: &[u8],
+) -> Result<String, CodecError> {
+    Ok(transcode(from, to, data)?
+        .iter()
+        .map(|c| *c as char)
+        .collect::<String>())
+}
+
+/// Applies one step of a [Transcoder] to a byte slice.
+type Apply = Box<dyn Fn(&[u8]) -> Result<Vec<u8>, CodecError>>;
+
+/// Wraps a reader in another reader that applies one step of a [Transcoder].
+type ReaderLayer = Box<dyn for<'a> Fn(Box<dyn Read + 'a>) -> Box<dyn Read + 'a>>;
+
+/// One decoding or encoding step of a [Transcoder].
+struct Step {
+    apply: Apply,
+    layer: ReaderLayer,
+}
+
+/// A pipeline of decoding and encoding steps.
+///
+/// ```
+/// use zed_cryptopals_challenge::codec::transcode::Transcoder;
+/// use zed_cryptopals_challenge::codec::{b32::Base32, b64::Base64, hex::Hexadecimal};
+///
+/// // Hex that was Base64 encoded again, re-encoded as Base32.
+/// let pipeline = Transcoder::default()
+///     .decode(Base64 {})
+///     .decode(Hexadecimal {})
+///     .encode(Base32 {});
+/// assert_eq!(pipeline.run_to_string("NjY2ZjZm".as_bytes()), Ok("MZXW6===".to_string()));
+/// ```
+#[derive(Default)]
+pub struct Transcoder {
+    steps: Vec<Step>,
+}
+
+impl Transcoder {
+    /// A pipeline that decodes with `from`, then encodes with `to`.
+    pub fn new<F, T>(from: F, to: T) -> Transcoder
+    where
+        F: Codec + Clone + 'static,
+        T: Codec + Clone + 'static,
+    {
+        Transcoder::default().decode(from).encode(to)
+    }
+
+    /// Add a step that decodes the output of the pipeline so far with `codec`.
+    pub fn decode<C: Codec + Clone + 'static>(mut self, codec: C) -> Transcoder {
+        let layer_codec = codec.clone();
+        self.steps.push(Step {
+            apply: Box::new(move |data| codec.try_to_plain(data)),
+            layer: Box::new(move |reader| {
+                Box::new(DecoderReader::new(reader, layer_codec.clone()))
+            }),
+        });
+        self
+    }
+
+    /// Add a step that encodes the output of the pipeline so far with `codec`.
+    pub fn encode<C: Codec + Clone + 'static>(mut self, codec: C) -> Transcoder {
+        let layer_codec = codec.clone();
+        self.steps.push(Step {
+            apply: Box::new(move |data| codec.try_encode(data)),
+            layer: Box::new(move |reader| {
+                Box::new(EncoderReader::new(reader, layer_codec.clone()))
+            }),
+        });
+        self
+    }
+
+    /// Chain `next` after this pipeline, so that `next` runs over the output of this one.
+    pub fn then(mut self, next: Transcoder) -> Transcoder {
+        self.steps.extend(next.steps);
+        self
+    }
+
+    /// Run every step of the pipeline over `data`.
+    pub fn run(&self, data: &[u8]) -> Result<Vec<u8>, CodecError> {
+        self.steps
+            .iter()
+            .try_fold(data.to_vec(), |data, step| (step.apply)(&data))
+    }
+
+    /// Convenience function that wraps [Transcoder::run]
+    /// to return the transcoded content as a String.
+    pub fn run_to_string(&self, data: &[u8]) -> Result<String, CodecError> {
+        Ok(self
+            .run(data)?
+            .iter()
+            .map(|c| *c as char)
+            .collect::<String>())
+    }
+
+    /// Run every step of the pipeline over the content of `reader`,
+    /// writing the output to `writer` as it is produced.
+    ///
+    /// Returns the number of bytes written.
+    /// Malformed content fails with [io::ErrorKind::InvalidData], wrapping a [CodecError].
+    pub fn stream<R: Read, W: Write>(&self, reader: R, writer: &mut W) -> io::Result<u64> {
+        let mut reader = self
+            .steps
+            .iter()
+            .fold(Box::new(reader) as Box<dyn Read>, |reader, step| {
+                (step.layer)(reader)
+            });
+        io::copy(&mut reader, writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::adapter::CodecAPI;
+    use crate::codec::b32::Base32;
+    use crate::codec::b64::{Base64, Base64Engine};
+    use crate::codec::hex::Hexadecimal;
+    use crate::codec::wrap::{LineEnding, Wrapped};
+
+    const HEX: &str = "49276d206b696c6c696e6720796f757220627261696e206c696b65206120706f69736f6e6f7573206d757368726f6f6d";
+    const B64: &str = "SSdtIGtpbGxpbmcgeW91ciBicmFpbiBsaWtlIGEgcG9pc29ub3VzIG11c2hyb29t";
+
+    #[test]
+    fn test_transcode() {
+        assert_eq!(
+            transcode_to_string(&Hexadecimal {}, &Base64 {}, HEX.as_bytes()),
+            Ok(B64.to_string())
+        );
+        assert_eq!(
+            transcode_to_string(&Base64 {}, &Hexadecimal {}, B64.as_bytes()),
+            Ok(HEX.to_string())
+        );
+        assert_eq!(
+            transcode_to_string(&Base32 {}, &Base64Engine::url_safe(), "7P736PQ=".as_bytes()),
+            Ok("-_-_Pg==".to_string())
+        );
+    }
+
+    #[test]
+    fn test_transcode_error() {
+        assert_eq!(
+            transcode(&Hexadecimal {}, &Base64 {}, "4927x".as_bytes()),
+            Err(CodecError::InvalidCharacter {
+                offset: 4,
+                byte: b'x'
+            })
+        );
+    }
+
+    #[test]
+    fn test_transcoder_chain() {
+        let pipeline =
+            Transcoder::new(Hexadecimal {}, Base64 {}).then(Transcoder::new(Base64 {}, Base32 {}));
+        assert_eq!(
+            pipeline.run(HEX.as_bytes()),
+            transcode(&Hexadecimal {}, &Base32 {}, HEX.as_bytes())
+        );
+
+        let double_encoded = Base64 {}.encode(HEX.as_bytes());
+        let pipeline = Transcoder::default()
+            .decode(Base64 {})
+            .then(Transcoder::new(Hexadecimal {}, Base64 {}));
+        assert_eq!(pipeline.run_to_string(&double_encoded), Ok(B64.to_string()));
+
+        assert_eq!(
+            Transcoder::default().run_to_string(B64.as_bytes()),
+            Ok(B64.to_string())
+        );
+    }
+
+    #[test]
+    fn test_transcoder_stream() {
+        // Long enough to be read and encoded in several pieces.
+        let hex = HEX.repeat(200);
+        let wrapped = Wrapped::new(Base64 {}, 60, LineEnding::CrLf);
+        let pipelines = [
+            Transcoder::new(Hexadecimal {}, wrapped),
+            Transcoder::new(Hexadecimal {}, wrapped).then(Transcoder::new(wrapped, Base32 {})),
+        ];
+
+        for pipeline in pipelines {
+            let mut res: Vec<u8> = Vec::new();
+            let written = pipeline.stream(hex.as_bytes(), &mut res).unwrap();
+
+            assert_eq!(written as usize, res.len());
+            assert_eq!(Ok(res), pipeline.run(hex.as_bytes()));
+        }
+    }
+
+    #[test]
+    fn test_transcoder_stream_error() {
+        let mut res: Vec<u8> = Vec::new();
+        let err = Transcoder::new(Base64 {}, Hexadecimal {})
+            .stream("YWJj-WJj".as_bytes(), &mut res)
+            .unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.get_ref().and_then(|e| e.downcast_ref::<CodecError>()),
+            Some(&CodecError::InvalidCharacter {
+                offset: 4,
+                byte: b'-'
+            })
+        );
+    }
+}
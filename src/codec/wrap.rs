@@ -0,0 +1,310 @@
+//! Line wrapping for textual encodings, as used by MIME and PEM.
+//!
+//! Encoded content is often wrapped at a fixed column,
+//! like the Base64 files provided with the Cryptopals challenges.
+//! [Wrapped] adapts any [Codec] to produce and accept such content.
+
+use super::adapter::{CodePointMap, Codec, CodecAPI};
+use super::error::CodecError;
+use std::io::{self, Write};
+
+/// Line ending inserted between wrapped lines.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LineEnding {
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    pub fn as_bytes(&self) -> &'static [u8] {
+        match self {
+            LineEnding::Lf => b"\n",
+            LineEnding::CrLf => b"\r\n",
+        }
+    }
+}
+
+/// Adapter around another codec that wraps encoded output at `column`,
+/// and ignores ASCII whitespace when decoding.
+///
+/// Whitespace is removed before the content is split into quanta,
+/// so a line break in the middle of a quantum doesn't shift the quanta that follow it.
+/// A `column` of `0` disables wrapping.
+#[derive(Copy, Clone, Debug)]
+pub struct Wrapped<C: Codec> {
+    codec: C,
+    column: usize,
+    line_ending: LineEnding,
+}
+
+impl<C: Codec> Wrapped<C> {
+    pub fn new(codec: C, column: usize, line_ending: LineEnding) -> Wrapped<C> {
+        Wrapped {
+            codec,
+            column,
+            line_ending,
+        }
+    }
+
+    /// Wrap at 76 columns with CRLF line endings, per
+    /// [RFC 2045 §6.8](https://www.rfc-editor.org/rfc/rfc2045#section-6.8).
+    pub fn mime(codec: C) -> Wrapped<C> {
+        Wrapped::new(codec, 76, LineEnding::CrLf)
+    }
+
+    /// Wrap at 64 columns with LF line endings, per
+    /// [RFC 7468 §2](https://www.rfc-editor.org/rfc/rfc7468#section-2).
+    pub fn pem(codec: C) -> Wrapped<C> {
+        Wrapped::new(codec, 64, LineEnding::Lf)
+    }
+
+    /// Break `encoded` into lines,
+    /// as if `position` bytes of encoded content were already written on previous lines.
+    fn wrap(&self, encoded: Vec<u8>, position: usize) -> Vec<u8> {
+        let mut res: Vec<u8> = Vec::new();
+        let mut writer = LineWrapWriter::new(&mut res, self.column, self.line_ending);
+        writer.written = position;
+        writer
+            .write_all(&encoded)
+            .expect("writing to a Vec can't fail");
+        res
+    }
+
+    /// Remove whitespace from `data`,
+    /// returning the remaining content and the position of each remaining byte in `data`.
+    fn strip(&self, data: &[u8]) -> (Vec<u8>, Vec<usize>) {
+        data.iter()
+            .enumerate()
+            .filter(|(_, c)| !self.skips(**c))
+            .map(|(i, c)| (*c, i))
+            .unzip()
+    }
+}
+
+impl<C: Codec> CodePointMap for Wrapped<C> {
+    fn map_codepoint_to_plain(&self, v: u8) -> Option<u8> {
+        self.codec.map_codepoint_to_plain(v)
+    }
+
+    fn map_plain_to_codepoint(&self, c: u8) -> Option<u8> {
+        self.codec.map_plain_to_codepoint(c)
+    }
+}
+
+impl<C: Codec> CodecAPI for Wrapped<C> {
+    fn raw_encode(&self, v: &[u8]) -> Vec<u8> {
+        self.codec.raw_encode(v)
+    }
+
+    fn raw_to_plain(&self, v: &[u8]) -> Vec<u8> {
+        self.codec.raw_to_plain(v)
+    }
+
+    fn get_chunksize(&self) -> usize {
+        self.codec.get_chunksize()
+    }
+
+    fn get_encoded_chunksize(&self) -> usize {
+        self.codec.get_encoded_chunksize()
+    }
+
+    fn is_textual(&self) -> bool {
+        self.codec.is_textual()
+    }
+
+    fn skips(&self, c: u8) -> bool {
+        c.is_ascii_whitespace() || self.codec.skips(c)
+    }
+
+    /// Encode with the wrapped codec, then break the output into lines.
+    fn encode(&self, data: &[u8]) -> Vec<u8> {
+        self.wrap(self.codec.encode(data), 0)
+    }
+
+    fn try_encode(&self, data: &[u8]) -> Result<Vec<u8>, CodecError> {
+        Ok(self.wrap(self.codec.try_encode(data)?, 0))
+    }
+
+    /// Continue the lines of content that was already encoded.
+    /// Line endings are counted from the position `offset` bytes encode to.
+    fn try_encode_at(&self, data: &[u8], offset: usize) -> Result<Vec<u8>, CodecError> {
+        let position = offset / self.get_chunksize() * self.get_encoded_chunksize();
+        Ok(self.wrap(self.codec.try_encode(data)?, position))
+    }
+
+    fn to_plain(&self, data: &[u8]) -> Vec<u8> {
+        self.codec.to_plain(&self.strip(data).0)
+    }
+
+    /// Decode strictly with the wrapped codec, after removing whitespace.
+    /// Offsets in errors are positions in `data`, whitespace included.
+    fn try_to_plain(&self, data: &[u8]) -> Result<Vec<u8>, CodecError> {
+        let (content, positions) = self.strip(data);
+        self.codec.try_to_plain(&content).map_err(|e| {
+            e.map_offset(|offset| positions.get(offset).copied().unwrap_or(data.len()))
+        })
+    }
+}
+
+impl<C: Codec> Codec for Wrapped<C> {}
+
+/// Writer that inserts a line ending into the content written to it every `column` bytes.
+///
+/// No line ending is written after the last line.
+/// A `column` of `0` disables wrapping.
+pub struct LineWrapWriter<W: Write> {
+    inner: W,
+    column: usize,
+    line_ending: LineEnding,
+    written: usize,
+}
+
+impl<W: Write> LineWrapWriter<W> {
+    pub fn new(inner: W, column: usize, line_ending: LineEnding) -> LineWrapWriter<W> {
+        LineWrapWriter {
+            inner,
+            column,
+            line_ending,
+            written: 0,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for LineWrapWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.column == 0 {
+            return self.inner.write(buf);
+        }
+        let mut rest = buf;
+        while !rest.is_empty() {
+            // Only end a line once there is more content for the next one.
+            if self.written > 0 && self.written.is_multiple_of(self.column) {
+                self.inner.write_all(self.line_ending.as_bytes())?;
+            }
+            let room = self.column - self.written % self.column;
+            let (line, tail) = rest.split_at(room.min(rest.len()));
+            self.inner.write_all(line)?;
+            self.written += line.len();
+            rest = tail;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::b64::Base64;
+    use crate::codec::hex::Hexadecimal;
+    use crate::codec::stream::{DecoderReader, EncoderWriter};
+    use std::io::Read;
+
+    const PLAIN: &str = "And here be a bit longer text. Let's see how it goes!";
+    const ENCODED: &str =
+        "QW5kIGhlcmUgYmUgYSBiaXQgbG9uZ2VyIHRleHQuIExldCdzIHNlZSBob3cgaXQgZ29lcyE=";
+
+    fn factory() -> Wrapped<Base64> {
+        Wrapped::new(Base64 {}, 10, LineEnding::Lf)
+    }
+
+    #[test]
+    fn test_encode_wrapped() {
+        let expected = "QW5kIGhlcm\nUgYmUgYSBi\naXQgbG9uZ2\nVyIHRleHQu\nIExldCdzIH\nNlZSBob3cg\naXQgZ29lcy\nE=";
+        assert_eq!(factory().encode_to_string(PLAIN.as_bytes()), expected);
+
+        let crlf = Wrapped::new(Base64 {}, 36, LineEnding::CrLf);
+        assert_eq!(
+            crlf.encode_to_string(PLAIN.as_bytes()),
+            format!("{}\r\n{}", &ENCODED[..36], &ENCODED[36..])
+        );
+
+        let unwrapped = Wrapped::new(Base64 {}, 0, LineEnding::Lf);
+        assert_eq!(unwrapped.encode_to_string(PLAIN.as_bytes()), ENCODED);
+    }
+
+    #[test]
+    fn test_decode_skips_whitespace() {
+        let cases = [
+            "QW5kIGhlcm\nUgYmUgYSBi\naXQgbG9uZ2\nVyIHRleHQu\nIExldCdzIH\nNlZSBob3cg\naXQgZ29lcy\nE=",
+            "QW5kIGhlcmUgYmUgYSBiaXQgbG9uZ2VyIHRleHQu\r\nIExldCdzIHNlZSBob3cgaXQgZ29lcyE=\r\n",
+            " QW5k IGhl\tcmUg YmUg YSBi aXQg bG9u Z2Vy IHRl eHQu IExl dCdz IHNl ZSBo b3cg aXQg Z29l cyE= \n",
+        ];
+        for case in cases {
+            assert_eq!(factory().to_plain_string(case.as_bytes()), PLAIN);
+            assert_eq!(
+                factory().try_to_plain_string(case.as_bytes()),
+                Ok(PLAIN.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn test_try_decode_error_offsets() {
+        assert_eq!(
+            factory().try_to_plain("YWJj\nYW-j".as_bytes()),
+            Err(CodecError::InvalidCharacter {
+                offset: 7,
+                byte: b'-'
+            })
+        );
+        assert_eq!(
+            factory().try_to_plain("YQ\n==\nYWJj".as_bytes()),
+            Err(CodecError::InvalidPadding { offset: 3 })
+        );
+        assert_eq!(
+            Wrapped::pem(Hexadecimal {}).try_to_plain("61\n626".as_bytes()),
+            Err(CodecError::OddLength { len: 5 })
+        );
+    }
+
+    #[test]
+    fn test_borrowed_codec() {
+        let codec = Base64 {};
+        let borrowed = Wrapped::new(&codec, 10, LineEnding::Lf);
+        assert_eq!(
+            borrowed.encode(PLAIN.as_bytes()),
+            factory().encode(PLAIN.as_bytes())
+        );
+        assert_eq!(
+            borrowed.try_to_plain("YWJj\nYW-j".as_bytes()),
+            factory().try_to_plain("YWJj\nYW-j".as_bytes())
+        );
+    }
+
+    #[test]
+    fn test_encoder_writer_wrapped() {
+        let mut writer = EncoderWriter::new(Vec::new(), factory());
+        for chunk in PLAIN.as_bytes().chunks(5) {
+            writer.write_all(chunk).unwrap();
+        }
+        assert_eq!(writer.finish().unwrap(), factory().encode(PLAIN.as_bytes()));
+    }
+
+    #[test]
+    fn test_stream_wrapped() {
+        let mut encoded: Vec<u8> = Vec::new();
+        let mut writer = EncoderWriter::new(
+            LineWrapWriter::new(&mut encoded, 10, LineEnding::Lf),
+            Base64 {},
+        );
+        for chunk in PLAIN.as_bytes().chunks(7) {
+            writer.write_all(chunk).unwrap();
+        }
+        writer.finish().unwrap();
+        assert_eq!(encoded, factory().encode(PLAIN.as_bytes()));
+
+        let mut decoded: Vec<u8> = Vec::new();
+        DecoderReader::new(encoded.as_slice(), factory())
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, PLAIN.as_bytes());
+    }
+}
@@ -0,0 +1,144 @@
+//! Bitflipping attacks on unauthenticated CBC ciphertexts.
+//!
+//! In CBC mode, each plaintext block is XORed with the previous ciphertext block after it is
+//! decrypted. So flipping bits of one ciphertext block flips the same bits of the next
+//! plaintext block, while scrambling the plaintext block it decrypts to.
+//! Knowing some plaintext is enough to turn it into any chosen plaintext of the same length.
+//!
+//! [bitflip_mask] and [apply_mask] don't depend on the mode, so they work for CTR too,
+//! where flipping bits of the ciphertext flips the same bits of the plaintext in place.
+
+use crate::crack::error::CrackError;
+use crate::crack::oracle::{EncryptionOracle, ValidityOracle};
+use crate::crack::xor::fixed_xor;
+
+/// The mask that turns `known` plaintext into `chosen` plaintext when XORed into the ciphertext
+/// that controls it.
+///
+/// Returns [CrackError::LengthMismatch] if `known` and `chosen` have different lengths.
+pub fn bitflip_mask(known: &[u8], chosen: &[u8]) -> Result<Vec<u8>, CrackError> {
+    fixed_xor(known, chosen)
+}
+
+/// XOR `mask` into a copy of `ciphertext`, from `offset`.
+///
+/// Returns [CrackError::LengthMismatch] if `mask` runs past the end of `ciphertext`,
+/// with the length of `ciphertext` and where `mask` would end.
+pub fn apply_mask(ciphertext: &[u8], offset: usize, mask: &[u8]) -> Result<Vec<u8>, CrackError> {
+    let end = offset.saturating_add(mask.len());
+    if end > ciphertext.len() {
+        return Err(CrackError::LengthMismatch {
+            left: ciphertext.len(),
+            right: end,
+        });
+    }
+    let mut res = ciphertext.to_vec();
+    for (c, m) in res[offset..end].iter_mut().zip(mask) {
+        *c ^= m;
+    }
+    Ok(res)
+}
+
+/// Forge a ciphertext that `oracle` accepts,
+/// by injecting `injected` into the plaintext it encrypts in CBC mode,
+/// as in <https://cryptopals.com/sets/2/challenges/16>.
+///
+/// `oracle` must put `prefix_len` bytes in front of the plaintext,
+/// and return the IV followed by the ciphertext.
+/// The plaintext is a sacrificial block, which the attack scrambles,
+/// followed by as many `A`s as `injected` is long, which the attack turns into `injected`.
+///
+/// Returns [CrackError::LengthMismatch] if `injected` doesn't fit in a block,
+/// and [CrackError::Rejected] if the oracle doesn't accept the forged ciphertext.
+pub fn bitflip<O: EncryptionOracle + ValidityOracle + ?Sized>(
+    oracle: &O,
+    prefix_len: usize,
+    block_size: usize,
+    injected: &[u8],
+) -> Result<Vec<u8>, CrackError> {
+    if injected.len() > block_size {
+        return Err(CrackError::LengthMismatch {
+            left: injected.len(),
+            right: block_size,
+        });
+    }
+    // Filler that completes the block the prefix ends in, then the sacrificial block.
+    let filler = (block_size - prefix_len % block_size) % block_size + block_size;
+    let placeholder = vec![b'A'; injected.len()];
+    let ciphertext = oracle.encrypt(&[&vec![b'A'; filler][..], &placeholder].concat())?;
+
+    // With the IV in front, the ciphertext byte at the offset of a plaintext byte
+    // is the byte of the previous block that it is XORed with.
+    let mask = bitflip_mask(&placeholder, injected)?;
+    let forged = apply_mask(&ciphertext, prefix_len + filler, &mask)?;
+    if !oracle.is_valid(&forged)? {
+        return Err(CrackError::Rejected);
+    }
+    Ok(forged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crack::oracle::challenges::BitflipOracle;
+    use crate::crack::oracle::wrap::Counting;
+    use crate::utils::rand::Rng;
+
+    fn factory() -> BitflipOracle {
+        BitflipOracle::cbc(Rng::from_seed(16))
+    }
+
+    #[test]
+    fn test_mask() {
+        let mask = bitflip_mask(b":admin<true:", b";admin=true;").unwrap();
+        assert_eq!(mask, b"\x01\x00\x00\x00\x00\x00\x01\x00\x00\x00\x00\x01");
+        assert_eq!(
+            apply_mask(b"abcdef", 2, &[0x20, 0x20]),
+            Ok(b"abCDef".to_vec())
+        );
+        assert_eq!(
+            apply_mask(b"abcdef", 5, &[0x20, 0x20]),
+            Err(CrackError::LengthMismatch { left: 6, right: 7 })
+        );
+        assert!(bitflip_mask(b"ab", b"abc").is_err());
+    }
+
+    /// <https://cryptopals.com/sets/2/challenges/16>
+    #[test]
+    fn test_bitflip() {
+        let oracle = Counting::new(factory());
+        let forged = bitflip(&oracle, BitflipOracle::PREFIX.len(), 16, b";admin=true;").unwrap();
+
+        assert_eq!(oracle.inner().is_valid(&forged), Ok(true));
+        assert_eq!(oracle.queries(), 2);
+
+        // The attack relies on knowing where the user data starts.
+        assert_eq!(
+            bitflip(&factory(), 50, 16, b";admin=true;"),
+            Err(CrackError::Rejected)
+        );
+        assert_eq!(
+            bitflip(&factory(), 32, 16, &[b'x'; 17]),
+            Err(CrackError::LengthMismatch {
+                left: 17,
+                right: 16
+            })
+        );
+    }
+
+    /// <https://cryptopals.com/sets/4/challenges/26>: the same mask, flipped in place.
+    #[test]
+    fn test_bitflip_ctr() {
+        let oracle = BitflipOracle::ctr(Rng::from_seed(26));
+        let placeholder = b"AadminAtrueA";
+        let ciphertext = oracle.encrypt(placeholder).unwrap();
+
+        let mask = bitflip_mask(placeholder, b";admin=true;").unwrap();
+        let nonce_len = 8;
+        let forged =
+            apply_mask(&ciphertext, nonce_len + BitflipOracle::PREFIX.len(), &mask).unwrap();
+
+        assert_eq!(oracle.is_valid(&ciphertext), Ok(false));
+        assert_eq!(oracle.is_valid(&forged), Ok(true));
+    }
+}
@@ -0,0 +1,168 @@
+//! Search a haystack of candidates for the ones that are encrypted.
+//!
+//! Given many ciphertext candidates, like the lines of a log,
+//! [single_byte_xor] brute forces every candidate and ranks them by how plausible
+//! their best decryption is, so the encrypted needles come out on top.
+
+use crate::codec::adapter::Codec;
+use crate::crack::pool::WorkerPool;
+use crate::crack::score::Scorer;
+use crate::crack::xor::{brute_decoded, try_decode_content};
+use crate::crack::DecryptResult;
+
+/// A candidate that was decrypted in a search,
+/// along with its position among the candidates searched.
+#[derive(Clone, Debug)]
+pub struct Match {
+    index: usize,
+    score: f64,
+    result: DecryptResult,
+}
+
+impl Match {
+    /// Zero-based position of this candidate among the candidates searched,
+    /// e.g. its line index.
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
+    /// Score of the best decryption of this candidate per byte,
+    /// which is what candidates of different lengths are ranked by.
+    /// See [Scorer::score_per_byte].
+    pub fn get_score(&self) -> f64 {
+        self.score
+    }
+
+    /// The best decryption of this candidate, with its key and score.
+    pub fn get_result(&self) -> DecryptResult {
+        self.result.clone()
+    }
+}
+
+/// Find the `top_n` `candidates` most likely to be encrypted with single-byte XOR,
+/// best score first.
+///
+/// Candidates are encoded with `codec`, and decryptions are rated with `scorer`.
+/// Candidates are ranked by [Scorer::score_per_byte] of their best decryption,
+/// so that long candidates don't lose out to short ones, or the other way around.
+/// Candidates that aren't valid in the encoding format of `codec` are skipped.
+pub fn single_byte_xor<I, T, S>(candidates: I, codec: &T, scorer: &S, top_n: usize) -> Vec<Match>
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+    T: Codec + Sync,
+    S: Scorer + Sync + ?Sized,
+{
+    single_byte_xor_in(&WorkerPool::default(), candidates, codec, scorer, top_n)
+}
+
+/// Like [single_byte_xor], but searches the candidates on the workers of `pool`,
+/// one candidate per worker.
+pub fn single_byte_xor_in<I, T, S>(
+    pool: &WorkerPool,
+    candidates: I,
+    codec: &T,
+    scorer: &S,
+    top_n: usize,
+) -> Vec<Match>
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+    T: Codec + Sync,
+    S: Scorer + Sync + ?Sized,
+{
+    let candidates = candidates
+        .into_iter()
+        .map(|candidate| candidate.as_ref().to_vec())
+        .collect::<Vec<Vec<u8>>>();
+    let indices = (0..candidates.len()).collect::<Vec<usize>>();
+
+    let mut matches = pool
+        .map(&indices, |index| {
+            let decoded = try_decode_content(codec, &candidates[*index]).ok()?;
+            let result = brute_decoded(codec, &decoded, scorer, 1).pop()?;
+            Some(Match {
+                index: *index,
+                score: scorer.score_per_byte(&result.decrypted_result),
+                result,
+            })
+        })
+        .into_iter()
+        .flatten()
+        .collect::<Vec<Match>>();
+    // Stable, so matches with the same score stay in candidate order.
+    matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+    matches.truncate(top_n);
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::adapter::CodecAPI;
+    use crate::codec::b64::Base64;
+    use crate::codec::hex::Hexadecimal;
+    use crate::crack::score::{AsciiScorer, ChiSquaredScorer};
+    use crate::crack::xor::repeating_key_xor;
+    use crate::utils::fs::read_file;
+    use crate::utils::rand::Rng;
+
+    fn factory() -> ChiSquaredScorer {
+        ChiSquaredScorer {}
+    }
+
+    #[test]
+    fn test_challenge_4() {
+        let lines = read_file("_data/set1/challenge4.txt");
+        let res = single_byte_xor(&lines, &Hexadecimal {}, &factory(), 3);
+
+        assert_eq!(res.len(), 3);
+        assert_eq!(res[0].get_index(), 170);
+        assert_eq!(res[0].get_result().get_key_byte(), Some(0x35));
+        assert_eq!(
+            res[0].get_result().get_decrypted_result(),
+            "Now that the party is jumping\n".as_bytes()
+        );
+        assert!(res[0].get_result().get_score() >= res[1].get_result().get_score());
+    }
+
+    #[test]
+    fn test_skips_invalid_candidates() {
+        let codec = Base64 {};
+        let needle = repeating_key_xor(b"the quick brown fox jumps over the lazy dog", &[0x42]);
+        let haystack = vec![
+            "this line of the log isn't Base64".to_string(),
+            codec.encode_to_string(&[0x9c, 0x13, 0xf7, 0x02, 0x5e, 0xaa, 0x01, 0xd0]),
+            codec.encode_to_string(&needle),
+        ];
+
+        let res = single_byte_xor(haystack.iter(), &codec, &factory(), 10);
+
+        assert_eq!(res.len(), 2);
+        assert_eq!(res[0].get_index(), 2);
+        assert_eq!(res[0].get_result().get_key(), codec.encode(&[0x42]));
+        assert_eq!(res[1].get_index(), 1);
+        assert!(single_byte_xor(haystack, &codec, &factory(), 0).is_empty());
+    }
+
+    #[test]
+    fn test_mixed_lengths() {
+        let codec = Hexadecimal {};
+        let needle = repeating_key_xor(b"Cooking MC's like a pound of bacon", &[0x58]);
+        let haystack = vec![
+            codec.encode_to_string(&Rng::from_seed(15).bytes(512)),
+            codec.encode_to_string(&needle),
+            codec.encode_to_string(&Rng::from_seed(16).bytes(128)),
+        ];
+
+        let res = single_byte_xor(&haystack, &codec, &AsciiScorer {}, 3);
+
+        assert_eq!(res.len(), 3);
+        assert_eq!(res[0].get_index(), 1);
+        assert_eq!(res[0].get_result().get_key_byte(), Some(0x58));
+        // The raw score of the long random candidate adds up to more than the needle's.
+        assert!(res[0].get_result().get_score() < res[1].get_result().get_score());
+        assert!(res[0].get_score() > res[1].get_score());
+        assert!(res[1].get_score() >= res[2].get_score());
+    }
+}
@@ -0,0 +1,635 @@
+//! Detect ciphertexts encrypted with a block cipher in ECB mode.
+//!
+//! ECB encrypts identical plaintext blocks to identical ciphertext blocks,
+//! while the blocks of any other mode, or of random data, practically never repeat.
+//! So repeated blocks give ECB away, as long as the plaintext has some repetition in it.
+//!
+//! With an [EncryptionOracle], the plaintext can be chosen to repeat,
+//! so [guess_mode] tells ECB from CBC with a single query,
+//! and [play_mode_game] measures how well it does over many rounds.
+//! [byte_at_a_time] goes further, and decrypts a secret the oracle appends to the plaintext,
+//! and [cut_and_paste] splices blocks from separate queries into a forged ciphertext.
+
+use crate::cipher::aes::BLOCK_SIZE;
+use crate::codec::adapter::Codec;
+use crate::codec::error::CodecError;
+use crate::crack::error::CrackError;
+use crate::crack::oracle::challenges::{EcbCbcOracle, Mode};
+use crate::crack::oracle::wrap::Counting;
+use crate::crack::oracle::EncryptionOracle;
+use crate::crack::xor::try_decode_content;
+use crate::padding::pkcs7;
+use crate::utils::fs;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+/// How much a ciphertext looks like it was encrypted in ECB mode.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct EcbReport {
+    blocks: usize,
+    repeated_blocks: usize,
+    score: f64,
+}
+
+impl EcbReport {
+    /// Number of whole blocks in the ciphertext. A final partial block is ignored.
+    pub fn get_blocks(&self) -> usize {
+        self.blocks
+    }
+
+    /// Number of blocks that repeat an earlier block.
+    pub fn get_repeated_blocks(&self) -> usize {
+        self.repeated_blocks
+    }
+
+    /// Likelihood that the ciphertext was encrypted in ECB mode, from `0` to `1`:
+    /// the fraction of blocks after the first that repeat an earlier block.
+    ///
+    /// Any score above `0` is a strong sign of ECB,
+    /// as blocks of other modes repeat with negligible probability.
+    pub fn get_score(&self) -> f64 {
+        self.score
+    }
+
+    /// Whether any block repeats.
+    pub fn is_likely_ecb(&self) -> bool {
+        self.repeated_blocks > 0
+    }
+}
+
+/// A ciphertext from a batch, along with its position in the batch.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct EcbMatch {
+    index: usize,
+    report: EcbReport,
+}
+
+impl EcbMatch {
+    /// Zero-based position of this ciphertext in the batch, e.g. its line index.
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
+    pub fn get_report(&self) -> EcbReport {
+        self.report
+    }
+}
+
+/// Count the repeated blocks of `block_size` bytes in `crypt_text`, encoded with `codec`.
+///
+/// # Panics
+///
+/// If `block_size` is `0`.
+pub fn detect<T: Codec>(
+    codec: &T,
+    crypt_text: &[u8],
+    block_size: usize,
+) -> Result<EcbReport, CodecError> {
+    Ok(count_repeats(
+        &try_decode_content(codec, crypt_text)?,
+        block_size,
+    ))
+}
+
+/// Rank `candidates`, encoded with `codec`, by how likely they are to be encrypted in ECB mode,
+/// and return the `top_n` most likely ones, best score first.
+///
+/// Candidates that aren't valid in the encoding format of `codec` are skipped.
+///
+/// # Panics
+///
+/// If `block_size` is `0`.
+pub fn detect_batch<I, T>(
+    candidates: I,
+    codec: &T,
+    block_size: usize,
+    top_n: usize,
+) -> Vec<EcbMatch>
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+    T: Codec,
+{
+    let mut matches = candidates
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, candidate)| {
+            let report = detect(codec, candidate.as_ref(), block_size).ok()?;
+            Some(EcbMatch { index, report })
+        })
+        .collect::<Vec<EcbMatch>>();
+    // Stable, so matches with the same score stay in candidate order.
+    matches.sort_by(|a, b| b.report.score.total_cmp(&a.report.score));
+    matches.truncate(top_n);
+    matches
+}
+
+/// Like [detect_batch], with a candidate on every line of the file at `path`.
+///
+/// Blank lines, like the one after a trailing newline, are skipped,
+/// and indices of matches are line indices in the file.
+pub fn detect_file<P, T>(
+    path: P,
+    codec: &T,
+    block_size: usize,
+    top_n: usize,
+) -> io::Result<Vec<EcbMatch>>
+where
+    P: AsRef<Path>,
+    T: Codec,
+{
+    let content = fs::read_bytes(path)?;
+    let (line_indices, lines): (Vec<usize>, Vec<&[u8]>) = content
+        .split(|c| *c == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .unzip();
+    let mut matches = detect_batch(lines, codec, block_size, top_n);
+    for m in matches.iter_mut() {
+        m.index = line_indices[m.index];
+    }
+    Ok(matches)
+}
+
+/// Guess whether `oracle` encrypts in ECB or CBC mode, with blocks of `block_size` bytes,
+/// from a single query.
+///
+/// The query is three blocks of the same byte,
+/// so that whatever the oracle adds in front, at least two whole blocks of it repeat under ECB.
+///
+/// # Panics
+///
+/// If `block_size` is `0`.
+pub fn guess_mode<O: EncryptionOracle + ?Sized>(
+    oracle: &O,
+    block_size: usize,
+) -> Result<Mode, CrackError> {
+    let ciphertext = oracle.encrypt(&vec![b'A'; 3 * block_size])?;
+    let report = count_repeats(&ciphertext, block_size);
+    Ok(if report.is_likely_ecb() {
+        Mode::Ecb
+    } else {
+        Mode::Cbc
+    })
+}
+
+/// Results of rounds of the ECB/CBC game played by [play_mode_game].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ModeGameReport {
+    rounds: usize,
+    correct: usize,
+    ecb_rounds: usize,
+}
+
+impl ModeGameReport {
+    pub fn get_rounds(&self) -> usize {
+        self.rounds
+    }
+
+    /// Number of rounds where the mode was guessed right.
+    pub fn get_correct(&self) -> usize {
+        self.correct
+    }
+
+    /// Number of rounds where the oracle picked ECB.
+    pub fn get_ecb_rounds(&self) -> usize {
+        self.ecb_rounds
+    }
+
+    /// Fraction of rounds where the mode was guessed right, or `0` if no rounds were played.
+    pub fn accuracy(&self) -> f64 {
+        if self.rounds == 0 {
+            return 0.0;
+        }
+        self.correct as f64 / self.rounds as f64
+    }
+}
+
+/// Play `rounds` rounds of the game of <https://cryptopals.com/sets/2/challenges/11>:
+/// `oracle` picks a mode for each round, and [guess_mode] guesses it.
+pub fn play_mode_game(oracle: &EcbCbcOracle, rounds: usize) -> Result<ModeGameReport, CrackError> {
+    let mut report = ModeGameReport::default();
+    for _ in 0..rounds {
+        let revealing = RevealingOracle {
+            oracle,
+            mode: Cell::new(None),
+        };
+        let guess = guess_mode(&revealing, BLOCK_SIZE)?;
+        let mode = revealing.mode.get().expect("guess_mode queries the oracle");
+        report.rounds += 1;
+        report.correct += (guess == mode) as usize;
+        report.ecb_rounds += (mode == Mode::Ecb) as usize;
+    }
+    Ok(report)
+}
+
+/// Passes queries on to an [EcbCbcOracle], remembering the mode it picked for the last one.
+struct RevealingOracle<'a> {
+    oracle: &'a EcbCbcOracle,
+    mode: Cell<Option<Mode>>,
+}
+
+impl EncryptionOracle for RevealingOracle<'_> {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, CrackError> {
+        let (ciphertext, mode) = self.oracle.encrypt_with_mode(plaintext)?;
+        self.mode.set(Some(mode));
+        Ok(ciphertext)
+    }
+}
+
+/// The largest block size [byte_at_a_time] looks for.
+const MAX_BLOCK_SIZE: usize = 256;
+
+/// What [byte_at_a_time] found out about an oracle, and the secret it recovered.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ByteAtATime {
+    block_size: usize,
+    prefix_len: usize,
+    secret: Vec<u8>,
+    queries: usize,
+}
+
+impl ByteAtATime {
+    pub fn get_block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Length of the unknown prefix the oracle puts in front of the plaintext.
+    pub fn get_prefix_len(&self) -> usize {
+        self.prefix_len
+    }
+
+    /// The secret the oracle appends to the plaintext.
+    pub fn get_secret(&self) -> Vec<u8> {
+        self.secret.clone()
+    }
+
+    /// Number of queries the attack made to the oracle.
+    pub fn get_queries(&self) -> usize {
+        self.queries
+    }
+}
+
+/// Recover the secret that `oracle` appends to the plaintext before encrypting it in ECB mode,
+/// as in <https://cryptopals.com/sets/2/challenges/12>
+/// and <https://cryptopals.com/sets/2/challenges/14>.
+///
+/// The oracle may also put a prefix of unknown length in front of the plaintext,
+/// as long as the prefix and the secret are the same for every query.
+///
+/// 1. The block size is how much the ciphertext grows by when the plaintext grows past a block.
+/// 1. The oracle must encrypt in ECB mode, as [guess_mode] confirms.
+/// 1. The prefix ends where changing a single byte of plaintext stops changing a block.
+/// 1. Each byte of the secret is then lined up as the last byte of a block,
+///    after bytes that are known, and matched against the blocks for every candidate byte.
+pub fn byte_at_a_time<O: EncryptionOracle + ?Sized>(oracle: &O) -> Result<ByteAtATime, CrackError> {
+    let oracle = Counting::new(oracle);
+    let (block_size, unknown_len) = find_block_size(&oracle)?;
+    if guess_mode(&oracle, block_size)? != Mode::Ecb {
+        return Err(CrackError::NotEcb);
+    }
+    let prefix_len = find_prefix_len(&oracle, block_size)?;
+    let secret_len = unknown_len - prefix_len;
+
+    // Filler that pads the prefix out to a whole number of blocks,
+    // so the plaintext starts at the block `start`.
+    let align = vec![0; (block_size - prefix_len % block_size) % block_size];
+    let start = (prefix_len + align.len()) / block_size;
+    let block = |ciphertext: &[u8], index: usize| -> Vec<u8> {
+        ciphertext
+            .chunks(block_size)
+            .nth(index)
+            .map(|b| b.to_vec())
+            .unwrap_or_default()
+    };
+
+    // With `fill` bytes of filler, secret byte `i` is the last byte of a block
+    // when `i % block_size == block_size - 1 - fill`.
+    let targets = (0..block_size)
+        .map(|fill| oracle.encrypt(&[&align[..], &vec![0; fill]].concat()))
+        .collect::<Result<Vec<Vec<u8>>, CrackError>>()?;
+
+    let mut known = vec![0; block_size - 1];
+    for i in 0..secret_len {
+        let fill = block_size - 1 - i % block_size;
+        let target = block(&targets[fill], start + i / block_size);
+        let window = known[known.len() - (block_size - 1)..].to_vec();
+
+        let mut found = None;
+        for c in 0..=255u8 {
+            let ciphertext = oracle.encrypt(&[&align[..], &window, &[c]].concat())?;
+            if block(&ciphertext, start) == target {
+                found = Some(c);
+                break;
+            }
+        }
+        known.push(found.ok_or(CrackError::NoMatch { offset: i })?);
+    }
+
+    Ok(ByteAtATime {
+        block_size,
+        prefix_len,
+        secret: known.split_off(block_size - 1),
+        queries: oracle.queries(),
+    })
+}
+
+/// Forge a ciphertext from `oracle`, which encrypts in ECB mode,
+/// that decrypts to its usual plaintext with `current` at the end replaced by `forged`,
+/// as in <https://cryptopals.com/sets/2/challenges/13>.
+///
+/// One query puts `forged` and its PKCS#7 padding in blocks of their own,
+/// and another pushes `current` to the start of the last block,
+/// so the blocks of `forged` can take its place.
+/// The oracle must place the plaintext at the same offset in every query,
+/// and pass the bytes of `forged` and its padding through unchanged.
+///
+/// Returns [CrackError::LengthMismatch] if the oracle adds less to the plaintext than `current`.
+pub fn cut_and_paste<O: EncryptionOracle + ?Sized>(
+    oracle: &O,
+    current: &[u8],
+    forged: &[u8],
+) -> Result<Vec<u8>, CrackError> {
+    let (block_size, unknown_len) = find_block_size(oracle)?;
+    if guess_mode(oracle, block_size)? != Mode::Ecb {
+        return Err(CrackError::NotEcb);
+    }
+    let prefix_len = find_prefix_len(oracle, block_size)?;
+
+    // Filler that pads the prefix out to a whole number of blocks, followed by the forged blocks.
+    let align = (block_size - prefix_len % block_size) % block_size;
+    let start = prefix_len + align;
+    let forged = pkcs7::pad(forged, block_size);
+    let ciphertext = oracle.encrypt(&[&vec![b'A'; align][..], &forged].concat())?;
+    let forged_blocks = &ciphertext[start..start + forged.len()];
+
+    // Everything up to `current`, which ends on a block boundary with the right filler.
+    let kept = unknown_len
+        .checked_sub(current.len())
+        .ok_or(CrackError::LengthMismatch {
+            left: unknown_len,
+            right: current.len(),
+        })?;
+    let fill = (block_size - kept % block_size) % block_size;
+    let ciphertext = oracle.encrypt(&vec![b'A'; fill])?;
+
+    Ok([&ciphertext[..kept + fill], forged_blocks].concat())
+}
+
+/// Find the block size of `oracle`,
+/// and the combined length of whatever it adds to the plaintext.
+fn find_block_size<O: EncryptionOracle + ?Sized>(oracle: &O) -> Result<(usize, usize), CrackError> {
+    let initial = oracle.encrypt(&[])?.len();
+    for len in 1..=MAX_BLOCK_SIZE {
+        let grown = oracle.encrypt(&vec![0; len])?.len();
+        if grown > initial {
+            // The content filled the last block exactly, so padding added a whole block.
+            return Ok((grown - initial, initial - len));
+        }
+    }
+    Err(CrackError::BlockSizeNotFound)
+}
+
+/// Find the length of the prefix `oracle` puts in front of the plaintext.
+///
+/// The first block that differs between two plaintexts that differ in their first byte is
+/// the block the prefix ends in. Pushing the differing byte along with filler,
+/// that block stops differing once the filler completes it.
+fn find_prefix_len<O: EncryptionOracle + ?Sized>(
+    oracle: &O,
+    block_size: usize,
+) -> Result<usize, CrackError> {
+    let first_difference = |fill: usize| -> Result<usize, CrackError> {
+        let a = oracle.encrypt(&[&vec![0; fill][..], &[1]].concat())?;
+        let b = oracle.encrypt(&[&vec![0; fill][..], &[2]].concat())?;
+        Ok(a.chunks(block_size)
+            .zip(b.chunks(block_size))
+            .position(|(x, y)| x != y)
+            .unwrap_or(a.len() / block_size))
+    };
+
+    let block = first_difference(0)?;
+    for fill in 1..=block_size {
+        if first_difference(fill)? > block {
+            return Ok((block + 1) * block_size - fill);
+        }
+    }
+    Err(CrackError::NotEcb)
+}
+
+fn count_repeats(data: &[u8], block_size: usize) -> EcbReport {
+    assert!(block_size > 0, "block size must be at least 1");
+    let mut seen: HashMap<&[u8], usize> = HashMap::new();
+    for block in data.chunks_exact(block_size) {
+        *seen.entry(block).or_insert(0) += 1;
+    }
+    let blocks = data.len() / block_size;
+    let repeated_blocks = blocks - seen.len();
+    let score = if blocks > 1 {
+        repeated_blocks as f64 / (blocks - 1) as f64
+    } else {
+        0.0
+    };
+    EcbReport {
+        blocks,
+        repeated_blocks,
+        score,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cipher::aes::Aes;
+    use crate::cipher::modes::{cbc, ctr, ecb as ecb_mode, process};
+    use crate::codec::adapter::CodecAPI;
+    use crate::codec::b64::Base64;
+    use crate::codec::hex::Hexadecimal;
+    use crate::codec::raw::Raw;
+    use crate::crack::oracle::challenges::{
+        EcbSuffixOracle, PaddingOracle, ProfileOracle, CHALLENGE_12_SECRET,
+    };
+    use crate::crack::oracle::{from_fn, ValidityOracle};
+    use crate::utils::rand::Rng;
+
+    const PLAINTEXT: &[u8] = b"YELLOW SUBMARINEYELLOW SUBMARINEWE ALL LIVE IN A YELLOW SUBMARINE";
+
+    fn factory() -> Aes {
+        Aes::new(b"fish and chips!!").unwrap()
+    }
+
+    /// `len` bytes that look random, from a keystream.
+    fn noise(seed: u64, len: usize) -> Vec<u8> {
+        let ctr = ctr::Ctr::cryptopals(factory(), seed).unwrap();
+        process(ctr, &vec![0; len]).unwrap()
+    }
+
+    #[test]
+    fn test_detect() {
+        let ecb = ecb_mode::encrypt(factory(), &PLAINTEXT[..64]).unwrap();
+        let report = detect(&Raw {}, &ecb, 16).unwrap();
+        assert_eq!(report.get_blocks(), 4);
+        assert_eq!(report.get_repeated_blocks(), 1);
+        assert!((report.get_score() - 1.0 / 3.0).abs() < 1e-9);
+        assert!(report.is_likely_ecb());
+
+        let cbc = cbc::encrypt(factory(), &[0; 16], &PLAINTEXT[..64]).unwrap();
+        let report = detect(&Hexadecimal {}, &Hexadecimal {}.encode(&cbc), 16).unwrap();
+        assert_eq!(report.get_repeated_blocks(), 0);
+        assert_eq!(report.get_score(), 0.0);
+        assert!(!report.is_likely_ecb());
+
+        let report = detect(&Raw {}, &[7; 40], 8).unwrap();
+        assert_eq!((report.get_blocks(), report.get_repeated_blocks()), (5, 4));
+        assert_eq!(report.get_score(), 1.0);
+        assert_eq!(detect(&Raw {}, &[7; 15], 16).unwrap().get_score(), 0.0);
+
+        assert!(detect(&Hexadecimal {}, b"not hex", 16).is_err());
+    }
+
+    /// Like <https://cryptopals.com/sets/1/challenges/8>.
+    #[test]
+    fn test_detect_batch() {
+        let codec = Hexadecimal {};
+        let mut lines = (0..200)
+            .map(|i| codec.encode_to_string(&noise(i, 160)))
+            .collect::<Vec<String>>();
+        let mut plaintext = noise(1000, 160);
+        plaintext.copy_within(16..32, 96);
+        lines[132] = codec.encode_to_string(&ecb_mode::encrypt(factory(), &plaintext).unwrap());
+        lines.push("not hex".to_string());
+
+        let res = detect_batch(&lines, &codec, 16, 3);
+
+        assert_eq!(res.len(), 3);
+        assert_eq!(res[0].get_index(), 132);
+        assert_eq!(res[0].get_report().get_repeated_blocks(), 1);
+        assert!(!res[1].get_report().is_likely_ecb());
+        assert_eq!(detect_batch(&lines, &codec, 16, 1000).len(), 200);
+    }
+
+    #[test]
+    fn test_detect_file() {
+        let ecb = ecb_mode::encrypt(factory(), &PLAINTEXT[..64]).unwrap();
+        let content = [
+            Hexadecimal {}.encode_to_string(&noise(1, 64)),
+            String::new(),
+            Hexadecimal {}.encode_to_string(&ecb),
+            String::new(),
+        ]
+        .join("\r\n");
+        let path = std::env::temp_dir().join(format!("ecb-test-{}.txt", std::process::id()));
+        fs::write_bytes(&path, content.as_bytes()).unwrap();
+        let res = detect_file(&path, &Hexadecimal {}, 16, 10);
+        std::fs::remove_file(&path).unwrap();
+
+        // The blank line and the one after the trailing newline aren't candidates.
+        let res = res.unwrap();
+        assert_eq!(res.len(), 2);
+        assert_eq!(res[0].get_index(), 2);
+        assert!(res[0].get_report().is_likely_ecb());
+        assert_eq!(res[1].get_index(), 0);
+        assert!(detect_file("_data/missing.txt", &Hexadecimal {}, 16, 1).is_err());
+    }
+
+    #[test]
+    fn test_guess_mode() {
+        let ecb = Counting::new(EcbSuffixOracle::with_random_prefix(
+            &mut Rng::from_seed(3),
+            b"secret",
+        ));
+        let cbc = Counting::new(PaddingOracle::new(Rng::from_seed(3)));
+
+        assert_eq!(guess_mode(&ecb, 16), Ok(Mode::Ecb));
+        assert_eq!(guess_mode(&cbc, 16), Ok(Mode::Cbc));
+        assert_eq!((ecb.queries(), cbc.queries()), (1, 1));
+    }
+
+    /// Like <https://cryptopals.com/sets/2/challenges/11>.
+    #[test]
+    fn test_play_mode_game() {
+        let oracle = EcbCbcOracle::new(Rng::from_seed(2024));
+        let report = play_mode_game(&oracle, 2000).unwrap();
+
+        assert_eq!(report.get_rounds(), 2000);
+        assert_eq!(report.get_correct(), 2000);
+        assert_eq!(report.accuracy(), 1.0);
+        assert!((900..1100).contains(&report.get_ecb_rounds()));
+        assert_eq!(play_mode_game(&oracle, 0).unwrap().accuracy(), 0.0);
+    }
+
+    fn challenge_secret() -> Vec<u8> {
+        Base64 {}.to_plain(CHALLENGE_12_SECRET.as_bytes())
+    }
+
+    /// <https://cryptopals.com/sets/2/challenges/12>
+    #[test]
+    fn test_byte_at_a_time() {
+        let oracle = EcbSuffixOracle::challenge_12(&mut Rng::from_seed(12));
+        let res = byte_at_a_time(&oracle).unwrap();
+
+        assert_eq!(res.get_block_size(), 16);
+        assert_eq!(res.get_prefix_len(), 0);
+        assert_eq!(res.get_secret(), challenge_secret());
+        assert!(res.get_secret().starts_with(b"Rollin' in my 5.0\n"));
+        assert!(res.get_queries() < 256 * challenge_secret().len());
+    }
+
+    /// <https://cryptopals.com/sets/2/challenges/14>, for prefix lengths on and around
+    /// each block boundary up to 3 blocks.
+    #[test]
+    fn test_byte_at_a_time_prefix() {
+        let secret = b"attack at dawn; bring snacks".to_vec();
+        for prefix_len in [0, 1, 7, 15, 16, 17, 31, 32, 33, 47] {
+            let aes = Aes::new(b"YELLOW SUBMARINE").unwrap();
+            let prefix = vec![b'p'; prefix_len];
+            let oracle = from_fn(|plaintext: &[u8]| {
+                let content = [&prefix[..], plaintext, &secret].concat();
+                Ok(ecb_mode::encrypt(&aes, &pkcs7::pad(&content, 16))?)
+            });
+
+            let res = byte_at_a_time(&oracle).unwrap();
+            assert_eq!(res.get_prefix_len(), prefix_len);
+            assert_eq!(res.get_secret(), secret);
+        }
+
+        let oracle = EcbSuffixOracle::challenge_14(&mut Rng::from_seed(14));
+        assert_eq!(
+            byte_at_a_time(&oracle).unwrap().get_secret(),
+            challenge_secret()
+        );
+    }
+
+    #[test]
+    fn test_byte_at_a_time_errors() {
+        let cbc = PaddingOracle::new(Rng::from_seed(1));
+        assert_eq!(byte_at_a_time(&cbc), Err(CrackError::NotEcb));
+
+        let fixed = from_fn(|_: &[u8]| Ok(vec![0; 32]));
+        assert_eq!(byte_at_a_time(&fixed), Err(CrackError::BlockSizeNotFound));
+    }
+
+    /// <https://cryptopals.com/sets/2/challenges/13>
+    #[test]
+    fn test_cut_and_paste() {
+        let oracle = Counting::new(ProfileOracle::new(&mut Rng::from_seed(13)));
+        let forged = cut_and_paste(&oracle, b"user", b"admin").unwrap();
+
+        let profile = oracle.inner().profile(&forged).unwrap();
+        assert_eq!(profile.get("role"), Some("admin"));
+        assert_eq!(profile.get("uid"), Some("10"));
+        assert_eq!(oracle.is_valid(&forged), Ok(true));
+        assert!(oracle.queries() < 100);
+
+        assert_eq!(
+            cut_and_paste(&oracle, &[b'x'; 100], b"admin"),
+            Err(CrackError::LengthMismatch {
+                left: 23,
+                right: 100
+            })
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! Errors returned by the fallible functions in [crate::crack].
+
+use crate::cipher::error::CipherError;
+use crate::formats::error::FormatError;
+use crate::padding::error::PaddingError;
+use std::fmt;
+
+/// Describes why an operation in [crate::crack] could not be performed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CrackError {
+    /// The operands of an operation that works byte-for-byte
+    /// have different lengths, `left` and `right`.
+    LengthMismatch { left: usize, right: usize },
+
+    /// A serialized model is malformed from `offset` onwards.
+    InvalidModel { offset: usize },
+
+    /// A batch of jobs was cancelled before every job completed.
+    Cancelled,
+
+    /// An oracle refused a query, because its budget of `budget` queries was spent.
+    BudgetExhausted { budget: usize },
+
+    /// An oracle could not process a query with its cipher.
+    Cipher(CipherError),
+
+    /// An oracle could not remove the padding of a decrypted query.
+    Padding(PaddingError),
+
+    /// An oracle could not parse a decrypted query.
+    Format(FormatError),
+
+    /// The ciphertexts of an oracle never grew by a whole block,
+    /// so it doesn't look like it encrypts with a block cipher.
+    BlockSizeNotFound,
+
+    /// An oracle doesn't encrypt in ECB mode, which the attack requires.
+    NotEcb,
+
+    /// No candidate reproduced the ciphertext for the unknown byte at `offset`,
+    /// e.g. because the oracle's output isn't deterministic.
+    NoMatch { offset: usize },
+
+    /// An oracle didn't accept a forged ciphertext.
+    Rejected,
+}
+
+impl fmt::Display for CrackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CrackError::LengthMismatch { left, right } => {
+                write!(f, "operand lengths {} and {} differ", left, right)
+            }
+            CrackError::InvalidModel { offset } => {
+                write!(f, "invalid serialized model at offset {}", offset)
+            }
+            CrackError::Cancelled => write!(f, "cancelled before every job completed"),
+            CrackError::BudgetExhausted { budget } => {
+                write!(f, "query budget of {} exhausted", budget)
+            }
+            CrackError::Cipher(e) => write!(f, "oracle cipher error: {}", e),
+            CrackError::Padding(e) => write!(f, "oracle padding error: {}", e),
+            CrackError::Format(e) => write!(f, "oracle format error: {}", e),
+            CrackError::BlockSizeNotFound => write!(f, "could not find the block size"),
+            CrackError::NotEcb => write!(f, "oracle doesn't encrypt in ECB mode"),
+            CrackError::NoMatch { offset } => {
+                write!(f, "no candidate matched the byte at offset {}", offset)
+            }
+            CrackError::Rejected => write!(f, "oracle rejected the forged ciphertext"),
+        }
+    }
+}
+
+impl std::error::Error for CrackError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CrackError::Cipher(e) => Some(e),
+            CrackError::Padding(e) => Some(e),
+            CrackError::Format(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<CipherError> for CrackError {
+    fn from(e: CipherError) -> CrackError {
+        CrackError::Cipher(e)
+    }
+}
+
+impl From<PaddingError> for CrackError {
+    fn from(e: PaddingError) -> CrackError {
+        CrackError::Padding(e)
+    }
+}
+
+impl From<FormatError> for CrackError {
+    fn from(e: FormatError) -> CrackError {
+        CrackError::Format(e)
+    }
+}
@@ -3,6 +3,14 @@
 //! This module contains utilities for encrypting and decrypting data.
 //! Implemented as Zed works through <https://cryptopals.com/>.
 
+pub mod cbc;
+pub mod detect;
+pub mod ecb;
+pub mod error;
+pub mod model;
+pub mod oracle;
+pub mod pool;
+pub mod score;
 pub mod xor;
 
 /// DecryptResult is used to store
@@ -14,8 +22,9 @@ pub mod xor;
 #[derive(Clone, Debug)]
 pub struct DecryptResult {
     key: Vec<u8>,
+    plain_key: Vec<u8>,
     decrypted_result: Vec<u8>,
-    score: usize,
+    score: f64,
 }
 
 impl DecryptResult {
@@ -23,9 +32,47 @@ impl DecryptResult {
         self.decrypted_result.clone()
     }
 
+    /// The key, encoded in the same format as the ciphertext that was attacked.
     pub fn get_key(&self) -> Vec<u8> {
         self.key.clone()
     }
+
+    /// The key as plain bytes.
+    pub fn get_plain_key(&self) -> Vec<u8> {
+        self.plain_key.clone()
+    }
+
+    /// The key byte, if the key is a single byte.
+    pub fn get_key_byte(&self) -> Option<u8> {
+        match self.plain_key.as_slice() {
+            [byte] => Some(*byte),
+            _ => None,
+        }
+    }
+
+    /// The score given to the decrypted result by a [crate::crack::score::Scorer].
+    pub fn get_score(&self) -> f64 {
+        self.score
+    }
+}
+
+/// How clearly the best of a list of `ranked` results, sorted best score first,
+/// beat the runner-up.
+///
+/// The difference between their scores is normalized by their magnitudes,
+/// so `0.0` is a tie and `1.0` is as clear a win as possible.
+/// Returns `None` if there is no runner-up.
+pub fn confidence_margin(ranked: &[DecryptResult]) -> Option<f64> {
+    match ranked {
+        [first, second, ..] => {
+            let scale = first.score.abs() + second.score.abs();
+            if scale == 0.0 {
+                return Some(0.0);
+            }
+            Some(((first.score - second.score) / scale).clamp(0.0, 1.0))
+        }
+        _ => None,
+    }
 }
 
 /// The Hamming distance between two byte slices is the number of bits that
@@ -49,6 +96,23 @@ mod tests {
         Base64 {}
     }
 
+    #[test]
+    fn test_confidence_margin() {
+        let result = |score| DecryptResult {
+            key: vec![],
+            plain_key: vec![],
+            decrypted_result: vec![],
+            score,
+        };
+
+        assert_eq!(confidence_margin(&[]), None);
+        assert_eq!(confidence_margin(&[result(3.0)]), None);
+        assert_eq!(confidence_margin(&[result(3.0), result(1.0)]), Some(0.5));
+        assert_eq!(confidence_margin(&[result(-1.0), result(-3.0)]), Some(0.5));
+        assert_eq!(confidence_margin(&[result(2.0), result(2.0)]), Some(0.0));
+        assert_eq!(confidence_margin(&[result(0.0), result(0.0)]), Some(0.0));
+    }
+
     #[test]
     fn hamming_distance_wokka() {
         let cases = [("this is a test", "wokka wokka!!!", 37)];
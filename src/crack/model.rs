@@ -0,0 +1,469 @@
+//! Frequency models trained from a corpus of the expected kind of plaintext.
+//!
+//! The scorers in [crate::crack::score] assume English text.
+//! A [FrequencyModel] learns what plaintext looks like from a local corpus instead,
+//! whether that is another language, source code, JSON, or log lines,
+//! and can be saved to disk and reloaded for later runs.
+
+use crate::crack::error::CrackError;
+use crate::crack::score::Scorer;
+use crate::utils::fs;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+/// Identifies the serialized format of a [FrequencyModel], and its version.
+const MAGIC: &[u8; 4] = b"ZFM1";
+
+/// Byte, byte pair and word counts of a corpus, used to score plaintexts like it.
+///
+/// Words are runs of ASCII letters, digits and underscores, and non-ASCII bytes,
+/// so that UTF-8 encoded words in other languages are kept whole.
+/// ASCII letters in words are counted case-insensitively.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FrequencyModel {
+    unigrams: [u64; 256],
+    bigrams: HashMap<(u8, u8), u64>,
+    words: HashMap<Vec<u8>, u64>,
+    /// Sum of `unigrams`.
+    total: u64,
+    /// Sum of `words`.
+    total_words: u64,
+}
+
+impl Default for FrequencyModel {
+    fn default() -> FrequencyModel {
+        FrequencyModel {
+            unigrams: [0; 256],
+            bigrams: HashMap::new(),
+            words: HashMap::new(),
+            total: 0,
+            total_words: 0,
+        }
+    }
+}
+
+impl FrequencyModel {
+    /// An empty model, which scores every byte the same until it is trained.
+    pub fn new() -> FrequencyModel {
+        FrequencyModel::default()
+    }
+
+    /// Build a model from the contents of the corpus file at `path`.
+    pub fn from_corpus<P: AsRef<Path>>(path: P) -> io::Result<FrequencyModel> {
+        let mut model = FrequencyModel::new();
+        model.train(&fs::read_bytes(path)?);
+        Ok(model)
+    }
+
+    /// Add the counts of `corpus` to this model.
+    pub fn train(&mut self, corpus: &[u8]) {
+        for c in corpus {
+            self.unigrams[*c as usize] += 1;
+        }
+        self.total += corpus.len() as u64;
+        for pair in corpus.windows(2) {
+            *self.bigrams.entry((pair[0], pair[1])).or_insert(0) += 1;
+        }
+        for word in words(corpus) {
+            *self.words.entry(word).or_insert(0) += 1;
+            self.total_words += 1;
+        }
+    }
+
+    /// Load a model saved with [FrequencyModel::save].
+    ///
+    /// A malformed file fails with [io::ErrorKind::InvalidData], wrapping a [CrackError].
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<FrequencyModel> {
+        FrequencyModel::from_bytes(&fs::read_bytes(path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Save this model to `path`, in the format of [FrequencyModel::to_bytes].
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write_bytes(path, &self.to_bytes())
+    }
+
+    /// Serialize this model.
+    ///
+    /// The format starts with the magic bytes `ZFM1`, followed by:
+    /// 1. The 256 byte counts.
+    /// 1. The number of byte pairs, then each pair and its count.
+    /// 1. The number of words, then the length of each word, the word, and its count.
+    ///
+    /// Numbers are unsigned LEB128 varints, and pairs and words are sorted,
+    /// so the same model always serializes to the same bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut res = MAGIC.to_vec();
+        for count in self.unigrams {
+            write_varint(&mut res, count);
+        }
+
+        let mut bigrams = self.bigrams.iter().collect::<Vec<_>>();
+        bigrams.sort();
+        write_varint(&mut res, bigrams.len() as u64);
+        for ((a, b), count) in bigrams {
+            res.extend([*a, *b]);
+            write_varint(&mut res, *count);
+        }
+
+        let mut words = self.words.iter().collect::<Vec<_>>();
+        words.sort();
+        write_varint(&mut res, words.len() as u64);
+        for (word, count) in words {
+            write_varint(&mut res, word.len() as u64);
+            res.extend(word);
+            write_varint(&mut res, *count);
+        }
+        res
+    }
+
+    /// Deserialize a model serialized with [FrequencyModel::to_bytes].
+    pub fn from_bytes(data: &[u8]) -> Result<FrequencyModel, CrackError> {
+        if !data.starts_with(MAGIC) {
+            return Err(CrackError::InvalidModel { offset: 0 });
+        }
+        let mut reader = ModelReader {
+            data,
+            offset: MAGIC.len(),
+        };
+        let mut model = FrequencyModel::new();
+
+        for count in model.unigrams.iter_mut() {
+            let start = reader.offset;
+            *count = reader.varint()?;
+            model.total = model
+                .total
+                .checked_add(*count)
+                .ok_or(CrackError::InvalidModel { offset: start })?;
+        }
+        for _ in 0..reader.varint()? {
+            let pair = reader.take(2)?;
+            let start = reader.offset - 2;
+            let pair = (pair[0], pair[1]);
+            if model.bigrams.insert(pair, reader.varint()?).is_some() {
+                return Err(CrackError::InvalidModel { offset: start });
+            }
+        }
+        for _ in 0..reader.varint()? {
+            let start = reader.offset;
+            let len = reader.varint()? as usize;
+            let word = reader.take(len)?.to_vec();
+            let count_start = reader.offset;
+            let count = reader.varint()?;
+            model.total_words =
+                model
+                    .total_words
+                    .checked_add(count)
+                    .ok_or(CrackError::InvalidModel {
+                        offset: count_start,
+                    })?;
+            if model.words.insert(word, count).is_some() {
+                return Err(CrackError::InvalidModel { offset: start });
+            }
+        }
+
+        if reader.offset != data.len() {
+            return Err(CrackError::InvalidModel {
+                offset: reader.offset,
+            });
+        }
+        Ok(model)
+    }
+}
+
+impl Scorer for FrequencyModel {
+    /// Average log-likelihood of each byte given the byte before it,
+    /// and of each word, with add-one smoothing for anything the corpus didn't contain.
+    ///
+    /// Empty data scores `0`.
+    fn score(&self, data: &[u8]) -> f64 {
+        let total = self.total as f64;
+        let mut log_likelihood = 0.0;
+
+        if let Some(first) = data.first() {
+            log_likelihood +=
+                (self.unigrams[*first as usize] as f64 + 1.0).ln() - (total + 256.0).ln();
+        }
+        for pair in data.windows(2) {
+            let count = self.bigrams.get(&(pair[0], pair[1])).copied().unwrap_or(0);
+            let given = self.unigrams[pair[0] as usize];
+            log_likelihood += (count as f64 + 1.0).ln() - (given as f64 + 256.0).ln();
+        }
+
+        let word_norm = (self.total_words as f64 + self.words.len() as f64 + 1.0).ln();
+        let mut count = data.len();
+        for word in words(data) {
+            let seen = self.words.get(&word).copied().unwrap_or(0);
+            log_likelihood += (seen as f64 + 1.0).ln() - word_norm;
+            count += 1;
+        }
+
+        if count == 0 {
+            return 0.0;
+        }
+        log_likelihood / count as f64
+    }
+
+    fn score_per_byte(&self, data: &[u8]) -> f64 {
+        self.score(data)
+    }
+}
+
+/// Split `data` into the words counted by a [FrequencyModel].
+fn words(data: &[u8]) -> impl Iterator<Item = Vec<u8>> + '_ {
+    data.split(|c| !(c.is_ascii_alphanumeric() || *c == b'_' || !c.is_ascii()))
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_ascii_lowercase())
+}
+
+/// Append `value` to `buf` as an unsigned LEB128 varint.
+fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+/// Cursor over a serialized [FrequencyModel].
+struct ModelReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> ModelReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], CrackError> {
+        let res = self
+            .data
+            .get(self.offset..self.offset.saturating_add(len))
+            .ok_or(CrackError::InvalidModel {
+                offset: self.offset,
+            })?;
+        self.offset += len;
+        Ok(res)
+    }
+
+    fn varint(&mut self) -> Result<u64, CrackError> {
+        let start = self.offset;
+        let mut value: u64 = 0;
+        for shift in (0..64).step_by(7) {
+            let c = self.take(1)?[0];
+            // Only the lowest bit of the 10th byte fits in a u64.
+            if shift == 63 && c > 1 {
+                return Err(CrackError::InvalidModel { offset: start });
+            }
+            value |= ((c & 0x7f) as u64) << shift;
+            if c & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(CrackError::InvalidModel { offset: start })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::raw::Raw;
+    use crate::crack::xor::{brute, repeating_key_xor};
+
+    const JSON_CORPUS: &str = r#"{"id": 1, "name": "alice", "roles": ["admin", "user"], "active": true}
+{"id": 2, "name": "bob", "roles": ["user"], "active": false}
+{"id": 3, "name": "carol", "roles": [], "active": true, "email": null}
+{"id": 4, "name": "dave", "roles": ["auditor"], "active": false, "tags": {"team": "ops"}}
+"#;
+
+    fn factory() -> FrequencyModel {
+        let mut model = FrequencyModel::new();
+        model.train(JSON_CORPUS.as_bytes());
+        model
+    }
+
+    #[test]
+    fn test_score() {
+        let model = factory();
+
+        assert!(
+            model.score(br#"{"id": 7, "name": "erin", "active": true}"#)
+                > model.score(b"Seven men met on the moor at noon.")
+        );
+        assert!(model.score(b"\"name\": \"frank\"") > model.score(b"\x02\xf1\x13\x9c\x00"));
+        assert_eq!(model.score(b""), 0.0);
+    }
+
+    #[test]
+    fn test_totals() {
+        let mut model = factory();
+        assert_eq!(model.total, JSON_CORPUS.len() as u64);
+        assert_eq!(
+            model.total_words,
+            words(JSON_CORPUS.as_bytes()).count() as u64
+        );
+
+        model.train(b"more words");
+        assert_eq!(model.total, JSON_CORPUS.len() as u64 + 10);
+        assert_eq!(
+            model.total_words,
+            words(JSON_CORPUS.as_bytes()).count() as u64 + 2
+        );
+
+        let loaded = FrequencyModel::from_bytes(&model.to_bytes()).unwrap();
+        assert_eq!(loaded.total, model.total);
+        assert_eq!(loaded.total_words, model.total_words);
+    }
+
+    #[test]
+    fn test_brute_json() {
+        let plain = r#"{"id": 5, "name": "mallory", "roles": ["user"], "active": true}"#;
+        let crypt_text = repeating_key_xor(plain.as_bytes(), &[0x1f]);
+
+        let res = brute(&Raw {}, crypt_text.as_slice(), &factory());
+
+        assert_eq!(res.get_key(), vec![0x1f]);
+        assert_eq!(res.get_decrypted_result(), plain.as_bytes());
+    }
+
+    #[test]
+    fn test_serialize() {
+        let model = factory();
+        let bytes = model.to_bytes();
+
+        assert!(bytes.starts_with(MAGIC));
+        assert_eq!(FrequencyModel::from_bytes(&bytes), Ok(model.clone()));
+        assert_eq!(
+            bytes,
+            FrequencyModel::from_bytes(&bytes).unwrap().to_bytes()
+        );
+        assert_eq!(
+            FrequencyModel::from_bytes(&FrequencyModel::new().to_bytes()),
+            Ok(FrequencyModel::new())
+        );
+
+        assert_eq!(
+            FrequencyModel::from_bytes(b"ZFM2"),
+            Err(CrackError::InvalidModel { offset: 0 })
+        );
+        assert_eq!(
+            FrequencyModel::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(CrackError::InvalidModel {
+                offset: bytes.len() - 1
+            })
+        );
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(
+            FrequencyModel::from_bytes(&trailing),
+            Err(CrackError::InvalidModel {
+                offset: bytes.len()
+            })
+        );
+    }
+
+    #[test]
+    fn test_serialize_malformed() {
+        let header = |bigrams: &[(u8, u8)]| {
+            let mut res = MAGIC.to_vec();
+            res.extend([0; 256]);
+            write_varint(&mut res, bigrams.len() as u64);
+            for (a, b) in bigrams {
+                res.extend([*a, *b, 1]);
+            }
+            res
+        };
+
+        // The 10th byte of a varint only has room for one bit.
+        let mut too_long = MAGIC.to_vec();
+        too_long.extend([0xff; 9]);
+        too_long.push(0x02);
+        assert_eq!(
+            FrequencyModel::from_bytes(&too_long),
+            Err(CrackError::InvalidModel { offset: 4 })
+        );
+        let mut max = MAGIC.to_vec();
+        write_varint(&mut max, u64::MAX);
+        assert_eq!(max[4 + 9], 0x01);
+        assert_eq!(
+            ModelReader {
+                data: &max,
+                offset: 4
+            }
+            .varint(),
+            Ok(u64::MAX)
+        );
+
+        let duplicate_pair = header(&[(b'a', b'b'), (b'a', b'b')]);
+        assert_eq!(
+            FrequencyModel::from_bytes(&duplicate_pair),
+            Err(CrackError::InvalidModel {
+                offset: 4 + 256 + 1 + 3
+            })
+        );
+
+        let mut duplicate_word = header(&[]);
+        write_varint(&mut duplicate_word, 2);
+        duplicate_word.extend([2, b'h', b'i', 1, 2, b'h', b'i', 1]);
+        assert_eq!(
+            FrequencyModel::from_bytes(&duplicate_word),
+            Err(CrackError::InvalidModel {
+                offset: 4 + 256 + 1 + 1 + 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_serialize_overflow() {
+        // Two byte counts of u64::MAX, which add up to more than a u64 holds.
+        let mut unigrams = MAGIC.to_vec();
+        write_varint(&mut unigrams, u64::MAX);
+        write_varint(&mut unigrams, u64::MAX);
+        unigrams.extend([0; 254 + 2]);
+        assert_eq!(unigrams.len(), 4 + 10 + 10 + 254 + 2);
+        assert_eq!(
+            FrequencyModel::from_bytes(&unigrams),
+            Err(CrackError::InvalidModel { offset: 14 })
+        );
+
+        // The same for two word counts.
+        let words = |counts: &[(&[u8], u64)]| {
+            let mut res = MAGIC.to_vec();
+            res.extend([0; 256 + 1]);
+            write_varint(&mut res, counts.len() as u64);
+            for (word, count) in counts {
+                write_varint(&mut res, word.len() as u64);
+                res.extend(*word);
+                write_varint(&mut res, *count);
+            }
+            res
+        };
+        let overflowing = words(&[(b"a", u64::MAX), (b"b", 1)]);
+        assert_eq!(
+            FrequencyModel::from_bytes(&overflowing),
+            Err(CrackError::InvalidModel {
+                offset: overflowing.len() - 1
+            })
+        );
+
+        // Counts that fit still score without overflowing.
+        let model = FrequencyModel::from_bytes(&words(&[(b"a", u64::MAX)])).unwrap();
+        assert!(model.score(b"a a").is_finite());
+    }
+
+    #[test]
+    fn test_corpus_files() {
+        let model = FrequencyModel::from_corpus("_data/set1/challenge4.txt").unwrap();
+        assert!(
+            model.score(b"7b5a4215415d544115415d5015455447414c155c46") > model.score(b"Hello!")
+        );
+
+        let path = std::env::temp_dir().join(format!("zfm-test-{}.zfm", std::process::id()));
+        model.save(&path).unwrap();
+        let loaded = FrequencyModel::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), model);
+
+        let err = FrequencyModel::load("_data/set1/challenge4.txt").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(FrequencyModel::from_corpus("_data/missing.txt").is_err());
+    }
+}
@@ -0,0 +1,521 @@
+//! The standard oracles of the Cryptopals challenges, keyed with random AES-128 keys.
+//!
+//! Every oracle takes its randomness from a [Rng],
+//! so a run can be reproduced by seeding it with [Rng::from_seed].
+//! Published IVs and nonces don't come from the [Rng] directly, see `IvSource`.
+
+use super::{EncryptionOracle, ValidityOracle};
+use crate::cipher::aes::{Aes, BLOCK_SIZE};
+use crate::cipher::error::CipherError;
+use crate::cipher::modes::{cbc, ctr, ecb, process, BlockMode};
+use crate::codec::adapter::CodecAPI;
+use crate::codec::b64::Base64;
+use crate::crack::error::CrackError;
+use crate::formats::kv::{profile_for, KvMap};
+use crate::padding::pkcs7;
+use crate::utils::rand::Rng;
+use std::sync::Mutex;
+
+/// A random AES-128 key.
+fn random_aes(rng: &mut Rng) -> Aes {
+    Aes::new(&rng.bytes(16)).expect("16 bytes is an AES-128 key")
+}
+
+/// Source of the IVs and nonces that oracles publish alongside their ciphertexts.
+///
+/// Anyone who sees the output of a [Rng] can work out its state, and every key it generated,
+/// so IVs and nonces are blocks of AES-CTR keystream under a separate random key instead.
+struct IvSource {
+    keystream: ctr::Ctr<Aes>,
+}
+
+impl IvSource {
+    fn new(rng: &mut Rng) -> IvSource {
+        IvSource {
+            keystream: ctr::Ctr::cryptopals(random_aes(rng), rng.next_u64())
+                .expect("the Cryptopals counter fits in an AES block"),
+        }
+    }
+
+    /// The next block of keystream.
+    fn block(&mut self) -> Vec<u8> {
+        let mut block = vec![0; BLOCK_SIZE];
+        self.keystream.process_block(&mut block);
+        block
+    }
+
+    /// The next 8 byte nonce.
+    fn nonce(&mut self) -> u64 {
+        let block = self.block();
+        u64::from_le_bytes(block[..NONCE_SIZE].try_into().expect("nonce is 8 bytes"))
+    }
+}
+
+/// Split the IV or nonce of `len` bytes off the front of `data`.
+fn split_iv(data: &[u8], len: usize) -> Result<(&[u8], &[u8]), CrackError> {
+    if data.len() < len {
+        return Err(CipherError::InvalidIvLength {
+            len: data.len(),
+            expected: len,
+        }
+        .into());
+    }
+    Ok(data.split_at(len))
+}
+
+/// The unknown string that challenges 12 and 14 append to the plaintext, in Base64.
+pub const CHALLENGE_12_SECRET: &str = "\
+    Um9sbGluJyBpbiBteSA1LjAKV2l0aCBteSByYWctdG9wIGRvd24gc28gbXkg\
+    aGFpciBjYW4gYmxvdwpUaGUgZ2lybGllcyBvbiBzdGFuZGJ5IHdhdmluZyBq\
+    dXN0IHRvIHNheSBoaQpEaWQgeW91IHN0b3A/IE5vLCBJIGp1c3QgZHJvdmUg\
+    YnkK";
+
+/// Encrypts `prefix || plaintext || secret` in ECB mode with PKCS#7 padding,
+/// as in challenges [12](https://cryptopals.com/sets/2/challenges/12)
+/// and [14](https://cryptopals.com/sets/2/challenges/14).
+///
+/// The prefix and the secret suffix are the same for every query.
+pub struct EcbSuffixOracle {
+    aes: Aes,
+    prefix: Vec<u8>,
+    secret: Vec<u8>,
+}
+
+impl EcbSuffixOracle {
+    /// An oracle that appends `secret`, without a prefix.
+    pub fn new(rng: &mut Rng, secret: &[u8]) -> EcbSuffixOracle {
+        EcbSuffixOracle {
+            aes: random_aes(rng),
+            prefix: Vec::new(),
+            secret: secret.to_vec(),
+        }
+    }
+
+    /// An oracle that appends `secret`,
+    /// and prepends between 0 and 47 random bytes, picked once.
+    pub fn with_random_prefix(rng: &mut Rng, secret: &[u8]) -> EcbSuffixOracle {
+        let aes = random_aes(rng);
+        let len = rng.range(0..=3 * BLOCK_SIZE - 1);
+        EcbSuffixOracle {
+            aes,
+            prefix: rng.bytes(len),
+            secret: secret.to_vec(),
+        }
+    }
+
+    /// The oracle of challenge 12, which appends [CHALLENGE_12_SECRET].
+    pub fn challenge_12(rng: &mut Rng) -> EcbSuffixOracle {
+        EcbSuffixOracle::new(rng, &Base64 {}.to_plain(CHALLENGE_12_SECRET.as_bytes()))
+    }
+
+    /// The oracle of challenge 14, which also prepends a random prefix.
+    pub fn challenge_14(rng: &mut Rng) -> EcbSuffixOracle {
+        EcbSuffixOracle::with_random_prefix(
+            rng,
+            &Base64 {}.to_plain(CHALLENGE_12_SECRET.as_bytes()),
+        )
+    }
+}
+
+impl EncryptionOracle for EcbSuffixOracle {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, CrackError> {
+        let content = [&self.prefix[..], plaintext, &self.secret].concat();
+        Ok(ecb::encrypt(&self.aes, &pkcs7::pad(&content, BLOCK_SIZE))?)
+    }
+}
+
+/// The mode an [EcbCbcOracle] picked for a query.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Mode {
+    Ecb,
+    Cbc,
+}
+
+/// Encrypts every query with a fresh random key, surrounded by 5 to 10 random bytes either side,
+/// in either ECB or CBC mode with a random IV, by the flip of a coin,
+/// as in [challenge 11](https://cryptopals.com/sets/2/challenges/11).
+pub struct EcbCbcOracle {
+    rng: Mutex<Rng>,
+    ivs: Mutex<IvSource>,
+}
+
+impl EcbCbcOracle {
+    pub fn new(mut rng: Rng) -> EcbCbcOracle {
+        EcbCbcOracle {
+            ivs: Mutex::new(IvSource::new(&mut rng)),
+            rng: Mutex::new(rng),
+        }
+    }
+
+    /// Like [EncryptionOracle::encrypt], also revealing the mode that was picked,
+    /// so that guesses of the mode can be checked.
+    pub fn encrypt_with_mode(&self, plaintext: &[u8]) -> Result<(Vec<u8>, Mode), CrackError> {
+        let mut rng = self.rng.lock().unwrap();
+        let aes = random_aes(&mut rng);
+        let before = rng.range(5..=10);
+        let before = rng.bytes(before);
+        let after = rng.range(5..=10);
+        let after = rng.bytes(after);
+        let content = pkcs7::pad(&[&before[..], plaintext, &after].concat(), BLOCK_SIZE);
+
+        if rng.coin() {
+            Ok((ecb::encrypt(&aes, &content)?, Mode::Ecb))
+        } else {
+            let iv = self.ivs.lock().unwrap().block();
+            Ok((cbc::encrypt(&aes, &iv, &content)?, Mode::Cbc))
+        }
+    }
+}
+
+impl EncryptionOracle for EcbCbcOracle {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, CrackError> {
+        Ok(self.encrypt_with_mode(plaintext)?.0)
+    }
+}
+
+/// Encrypts in CBC mode with PKCS#7 padding and a random IV, and checks the padding of
+/// ciphertexts, as in [challenge 17](https://cryptopals.com/sets/3/challenges/17).
+///
+/// Ciphertexts are the IV followed by the encrypted blocks, both ways.
+pub struct PaddingOracle {
+    aes: Aes,
+    ivs: Mutex<IvSource>,
+}
+
+impl PaddingOracle {
+    pub fn new(mut rng: Rng) -> PaddingOracle {
+        PaddingOracle {
+            aes: random_aes(&mut rng),
+            ivs: Mutex::new(IvSource::new(&mut rng)),
+        }
+    }
+}
+
+impl EncryptionOracle for PaddingOracle {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, CrackError> {
+        let iv = self.ivs.lock().unwrap().block();
+        let ciphertext = cbc::encrypt(&self.aes, &iv, &pkcs7::pad(plaintext, BLOCK_SIZE))?;
+        Ok([iv, ciphertext].concat())
+    }
+}
+
+impl ValidityOracle for PaddingOracle {
+    /// Whether the decryption of `ciphertext` has valid PKCS#7 padding.
+    ///
+    /// Fails if `ciphertext` is shorter than an IV, or not a whole number of blocks.
+    fn is_valid(&self, ciphertext: &[u8]) -> Result<bool, CrackError> {
+        let (iv, ciphertext) = split_iv(ciphertext, BLOCK_SIZE)?;
+        let plaintext = cbc::decrypt(&self.aes, iv, ciphertext)?;
+        Ok(pkcs7::unpad(&plaintext, BLOCK_SIZE).is_ok())
+    }
+}
+
+/// Encrypts user profiles in ECB mode with PKCS#7 padding, and decrypts them again,
+/// as in [challenge 13](https://cryptopals.com/sets/2/challenges/13).
+///
+/// Queries are email addresses, which are encrypted as the profile [profile_for] gives them.
+pub struct ProfileOracle {
+    aes: Aes,
+}
+
+impl ProfileOracle {
+    pub fn new(rng: &mut Rng) -> ProfileOracle {
+        ProfileOracle {
+            aes: random_aes(rng),
+        }
+    }
+
+    /// Decrypt and parse the profile in `ciphertext`.
+    pub fn profile(&self, ciphertext: &[u8]) -> Result<KvMap, CrackError> {
+        let padded = ecb::decrypt(&self.aes, ciphertext)?;
+        Ok(KvMap::parse_bytes(pkcs7::unpad(&padded, BLOCK_SIZE)?)?)
+    }
+}
+
+impl EncryptionOracle for ProfileOracle {
+    /// Encrypt the profile for the email address `plaintext`.
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, CrackError> {
+        let profile = profile_for(&String::from_utf8_lossy(plaintext));
+        Ok(ecb::encrypt(
+            &self.aes,
+            &pkcs7::pad(profile.as_bytes(), BLOCK_SIZE),
+        )?)
+    }
+}
+
+impl ValidityOracle for ProfileOracle {
+    /// Whether `ciphertext` is a profile with the role `admin`.
+    ///
+    /// A ciphertext with invalid padding, or that doesn't parse, is not valid.
+    fn is_valid(&self, ciphertext: &[u8]) -> Result<bool, CrackError> {
+        match self.profile(ciphertext) {
+            Ok(profile) => Ok(profile.get("role") == Some("admin")),
+            Err(CrackError::Padding(_) | CrackError::Format(_)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// The mode a [BitflipOracle] encrypts with.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum BitflipMode {
+    Cbc,
+    Ctr,
+}
+
+/// Size in bytes of the nonce a [BitflipOracle] puts in front of CTR ciphertexts.
+const NONCE_SIZE: usize = 8;
+
+/// Encrypts user data in a cookie-like string, and checks ciphertexts for an admin flag,
+/// as in challenges [16](https://cryptopals.com/sets/2/challenges/16) (CBC)
+/// and [26](https://cryptopals.com/sets/4/challenges/26) (CTR).
+///
+/// User data is quoted, so that it can't contain the admin flag itself,
+/// and placed between `comment1=cooking%20MCs;userdata=` and
+/// `;comment2=%20like%20a%20pound%20of%20bacon`.
+/// Ciphertexts are a random IV or 8 byte nonce, followed by the encrypted string.
+pub struct BitflipOracle {
+    aes: Aes,
+    mode: BitflipMode,
+    ivs: Mutex<IvSource>,
+}
+
+impl BitflipOracle {
+    /// What comes before the user data, which is public knowledge.
+    pub const PREFIX: &'static [u8] = b"comment1=cooking%20MCs;userdata=";
+    /// What comes after the user data.
+    pub const SUFFIX: &'static [u8] = b";comment2=%20like%20a%20pound%20of%20bacon";
+    const ADMIN: &'static [u8] = b";admin=true;";
+
+    /// An oracle that encrypts in CBC mode with PKCS#7 padding.
+    pub fn cbc(rng: Rng) -> BitflipOracle {
+        BitflipOracle::new(rng, BitflipMode::Cbc)
+    }
+
+    /// An oracle that encrypts in CTR mode, with the counter layout of Cryptopals.
+    pub fn ctr(rng: Rng) -> BitflipOracle {
+        BitflipOracle::new(rng, BitflipMode::Ctr)
+    }
+
+    fn new(mut rng: Rng, mode: BitflipMode) -> BitflipOracle {
+        BitflipOracle {
+            aes: random_aes(&mut rng),
+            mode,
+            ivs: Mutex::new(IvSource::new(&mut rng)),
+        }
+    }
+
+    /// Percent-encode the `;` and `=` in `data`, which separate fields.
+    fn quote(data: &[u8]) -> Vec<u8> {
+        data.iter()
+            .flat_map(|c| match c {
+                b';' => b"%3B".to_vec(),
+                b'=' => b"%3D".to_vec(),
+                _ => vec![*c],
+            })
+            .collect()
+    }
+}
+
+impl EncryptionOracle for BitflipOracle {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, CrackError> {
+        let content = [Self::PREFIX, &Self::quote(plaintext), Self::SUFFIX].concat();
+        let mut ivs = self.ivs.lock().unwrap();
+        match self.mode {
+            BitflipMode::Cbc => {
+                let iv = ivs.block();
+                let ciphertext = cbc::encrypt(&self.aes, &iv, &pkcs7::pad(&content, BLOCK_SIZE))?;
+                Ok([iv, ciphertext].concat())
+            }
+            BitflipMode::Ctr => {
+                let nonce = ivs.nonce();
+                let ciphertext = process(ctr::Ctr::cryptopals(&self.aes, nonce)?, &content)?;
+                Ok([nonce.to_le_bytes().to_vec(), ciphertext].concat())
+            }
+        }
+    }
+}
+
+impl ValidityOracle for BitflipOracle {
+    /// Whether the decryption of `ciphertext` contains `;admin=true;`.
+    ///
+    /// A CBC ciphertext with invalid padding is not valid.
+    fn is_valid(&self, ciphertext: &[u8]) -> Result<bool, CrackError> {
+        let plaintext = match self.mode {
+            BitflipMode::Cbc => {
+                let (iv, ciphertext) = split_iv(ciphertext, BLOCK_SIZE)?;
+                let padded = cbc::decrypt(&self.aes, iv, ciphertext)?;
+                match pkcs7::unpad(&padded, BLOCK_SIZE) {
+                    Ok(plaintext) => plaintext.to_vec(),
+                    Err(_) => return Ok(false),
+                }
+            }
+            BitflipMode::Ctr => {
+                let (nonce, ciphertext) = split_iv(ciphertext, NONCE_SIZE)?;
+                let nonce = u64::from_le_bytes(nonce.try_into().expect("nonce is 8 bytes"));
+                process(ctr::Ctr::cryptopals(&self.aes, nonce)?, ciphertext)?
+            }
+        };
+        Ok(plaintext
+            .windows(Self::ADMIN.len())
+            .any(|window| window == Self::ADMIN))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::raw::Raw;
+    use crate::crack::ecb;
+
+    fn factory() -> Rng {
+        Rng::from_seed(42)
+    }
+
+    /// The amount SplitMix64 adds to its state for every output.
+    const GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+    /// Undo `x ^ (x >> shift)`.
+    fn unshift(z: u64, shift: u32) -> u64 {
+        let mut x = z;
+        for _ in 0..64 / shift {
+            x = z ^ (x >> shift);
+        }
+        x
+    }
+
+    /// Multiplicative inverse of odd `a` modulo 2^64, by Newton's method.
+    fn inverse(a: u64) -> u64 {
+        let mut x = a;
+        for _ in 0..5 {
+            x = x.wrapping_mul(2u64.wrapping_sub(a.wrapping_mul(x)));
+        }
+        x
+    }
+
+    /// The 16 byte keys that a [Rng] could have generated just before it output
+    /// the first 8 bytes of `published`, by working its state out from them.
+    fn keys_before(published: &[u8]) -> Vec<Vec<u8>> {
+        let z = u64::from_le_bytes(published[..8].try_into().unwrap());
+        let z = unshift(z, 31).wrapping_mul(inverse(0x94d0_49bb_1331_11eb));
+        let z = unshift(z, 27).wrapping_mul(inverse(0xbf58_476d_1ce4_e5b9));
+        let state = unshift(z, 30);
+        (1..=8u64)
+            .map(|back| {
+                Rng::from_seed(state.wrapping_sub((back + 2).wrapping_mul(GAMMA))).bytes(16)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_published_ivs_keep_keys() {
+        // IVs straight from the Rng that generated the key give the key away.
+        let mut rng = factory();
+        let key = rng.bytes(16);
+        assert!(keys_before(&rng.bytes(16)).contains(&key));
+
+        let oracle = PaddingOracle::new(factory());
+        let ciphertext = oracle.encrypt(b"hello, world").unwrap();
+        let (iv, ciphertext) = ciphertext.split_at(BLOCK_SIZE);
+        for key in keys_before(iv) {
+            let aes = Aes::new(&key).unwrap();
+            let padded = pkcs7::pad(b"hello, world", BLOCK_SIZE);
+            assert_ne!(cbc::encrypt(&aes, iv, &padded).unwrap(), ciphertext);
+        }
+
+        let oracle = BitflipOracle::ctr(factory());
+        let ciphertext = oracle.encrypt(b"").unwrap();
+        let (nonce, ciphertext) = ciphertext.split_at(NONCE_SIZE);
+        let content = [BitflipOracle::PREFIX, BitflipOracle::SUFFIX].concat();
+        for key in keys_before(nonce) {
+            let aes = Aes::new(&key).unwrap();
+            let nonce = u64::from_le_bytes(nonce.try_into().unwrap());
+            let keystream = ctr::Ctr::cryptopals(&aes, nonce).unwrap();
+            assert_ne!(process(keystream, &content).unwrap(), ciphertext);
+        }
+    }
+
+    #[test]
+    fn test_ecb_suffix() {
+        let oracle = EcbSuffixOracle::new(&mut factory(), b"secret");
+        assert_eq!(oracle.encrypt(b"").unwrap().len(), 16);
+        assert_eq!(oracle.encrypt(&[0; 10]).unwrap().len(), 32);
+        assert_eq!(oracle.encrypt(b"a").unwrap(), oracle.encrypt(b"a").unwrap());
+
+        let ciphertext = oracle.encrypt(&[b'A'; 32]).unwrap();
+        assert_eq!(ciphertext[..16], ciphertext[16..32]);
+
+        let oracle = EcbSuffixOracle::with_random_prefix(&mut factory(), b"secret");
+        assert!(oracle.prefix.len() < 48);
+        let report = ecb::detect(&Raw {}, &oracle.encrypt(&[b'A'; 64]).unwrap(), 16).unwrap();
+        assert!(report.is_likely_ecb());
+    }
+
+    #[test]
+    fn test_ecb_cbc() {
+        let oracle = EcbCbcOracle::new(factory());
+        let mut modes = Vec::new();
+        for _ in 0..20 {
+            let (ciphertext, mode) = oracle.encrypt_with_mode(&[0; 48]).unwrap();
+            assert!([64, 80].contains(&ciphertext.len()));
+            let report = ecb::detect(&Raw {}, &ciphertext, 16).unwrap();
+            assert_eq!(report.is_likely_ecb(), mode == Mode::Ecb);
+            modes.push(mode);
+        }
+        assert!(modes.contains(&Mode::Ecb) && modes.contains(&Mode::Cbc));
+    }
+
+    #[test]
+    fn test_padding() {
+        let oracle = PaddingOracle::new(factory());
+        let mut ciphertext = oracle.encrypt(b"hello, world").unwrap();
+        assert_eq!(ciphertext.len(), 32);
+        assert_ne!(oracle.encrypt(b"hello, world").unwrap(), ciphertext);
+        assert_eq!(oracle.is_valid(&ciphertext), Ok(true));
+
+        // The last byte of the plaintext goes from 0x04 to 0x05.
+        ciphertext[15] ^= 0x01;
+        assert_eq!(oracle.is_valid(&ciphertext), Ok(false));
+
+        assert_eq!(
+            oracle.is_valid(&ciphertext[..8]),
+            Err(CrackError::Cipher(CipherError::InvalidIvLength {
+                len: 8,
+                expected: 16
+            }))
+        );
+        assert!(oracle.is_valid(&ciphertext[..20]).is_err());
+    }
+
+    #[test]
+    fn test_profile() {
+        let oracle = ProfileOracle::new(&mut factory());
+        let ciphertext = oracle.encrypt(b"foo@bar.com&role=admin").unwrap();
+        let profile = oracle.profile(&ciphertext).unwrap();
+
+        assert_eq!(profile.get("email"), Some("foo@bar.com&role=admin"));
+        assert_eq!(profile.get("role"), Some("user"));
+        assert_eq!(oracle.is_valid(&ciphertext), Ok(false));
+        assert_eq!(oracle.is_valid(&ciphertext[16..]), Ok(false));
+        assert!(oracle.is_valid(&ciphertext[1..]).is_err());
+    }
+
+    #[test]
+    fn test_bitflip() {
+        for oracle in [BitflipOracle::cbc(factory()), BitflipOracle::ctr(factory())] {
+            let ciphertext = oracle.encrypt(b"x;admin=true;").unwrap();
+            assert_eq!(oracle.is_valid(&ciphertext), Ok(false));
+            assert!(oracle.is_valid(&[0; 4]).is_err());
+        }
+        assert_eq!(BitflipOracle::quote(b"a;b=c"), b"a%3Bb%3Dc".to_vec());
+
+        // In CTR mode, flipping bits of the ciphertext flips the same bits of the plaintext.
+        let oracle = BitflipOracle::ctr(factory());
+        let mut ciphertext = oracle.encrypt(b"XadminXtrueX").unwrap();
+        let start = NONCE_SIZE + BitflipOracle::PREFIX.len();
+        for (i, (from, to)) in b"XadminXtrueX".iter().zip(b";admin=true;").enumerate() {
+            ciphertext[start + i] ^= from ^ to;
+        }
+        assert_eq!(oracle.is_valid(&ciphertext), Ok(true));
+    }
+}
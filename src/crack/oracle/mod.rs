@@ -0,0 +1,78 @@
+//! Oracles for adaptive chosen-plaintext and chosen-ciphertext attacks.
+//!
+//! An oracle answers queries with a key the attacker doesn't know:
+//! an [EncryptionOracle] encrypts chosen plaintext, a [DecryptionOracle] decrypts chosen
+//! ciphertext, and a [ValidityOracle] only says whether a ciphertext was accepted.
+//!
+//! Attacks are generic over these traits.
+//! The wrappers in [wrap] count, limit and log the queries an attack makes,
+//! which is how the efficiency of attacks is compared,
+//! and [challenges] builds the standard oracles of the Cryptopals challenges.
+//!
+//! Queries take `&self`, so an oracle can be queried from the workers of a
+//! [crate::crack::pool::WorkerPool]. A closure can be made into an oracle with [from_fn].
+
+pub mod challenges;
+pub mod wrap;
+
+use crate::crack::error::CrackError;
+
+/// Encrypts chosen plaintext.
+pub trait EncryptionOracle {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, CrackError>;
+}
+
+/// Decrypts chosen ciphertext.
+pub trait DecryptionOracle {
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, CrackError>;
+}
+
+/// Says whether a chosen ciphertext is accepted, e.g. whether its padding is valid,
+/// without revealing anything else about it.
+pub trait ValidityOracle {
+    fn is_valid(&self, ciphertext: &[u8]) -> Result<bool, CrackError>;
+}
+
+/// A shared reference to an oracle is an oracle too,
+/// so wrappers and attacks can borrow an oracle rather than take ownership of it.
+impl<O: EncryptionOracle + ?Sized> EncryptionOracle for &O {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, CrackError> {
+        (**self).encrypt(plaintext)
+    }
+}
+
+impl<O: DecryptionOracle + ?Sized> DecryptionOracle for &O {
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, CrackError> {
+        (**self).decrypt(ciphertext)
+    }
+}
+
+impl<O: ValidityOracle + ?Sized> ValidityOracle for &O {
+    fn is_valid(&self, ciphertext: &[u8]) -> Result<bool, CrackError> {
+        (**self).is_valid(ciphertext)
+    }
+}
+
+/// An oracle that answers queries with a closure. See [from_fn].
+#[derive(Clone, Copy, Debug)]
+pub struct FromFn<F>(F);
+
+/// Make an oracle out of closure `f`.
+///
+/// A closure returning bytes is an [EncryptionOracle],
+/// and a closure returning a `bool` is a [ValidityOracle].
+pub fn from_fn<F>(f: F) -> FromFn<F> {
+    FromFn(f)
+}
+
+impl<F: Fn(&[u8]) -> Result<Vec<u8>, CrackError>> EncryptionOracle for FromFn<F> {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, CrackError> {
+        (self.0)(plaintext)
+    }
+}
+
+impl<F: Fn(&[u8]) -> Result<bool, CrackError>> ValidityOracle for FromFn<F> {
+    fn is_valid(&self, ciphertext: &[u8]) -> Result<bool, CrackError> {
+        (self.0)(ciphertext)
+    }
+}
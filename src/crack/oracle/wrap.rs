@@ -0,0 +1,333 @@
+//! Wrappers that account for the queries made to an oracle.
+//!
+//! Each wrapper implements whichever oracle traits the oracle it wraps implements,
+//! and wrappers can be stacked, e.g. a [Counting] oracle around a [Budget] oracle.
+
+use super::{DecryptionOracle, EncryptionOracle, ValidityOracle};
+use crate::crack::error::CrackError;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Counts the queries made to an oracle.
+#[derive(Debug)]
+pub struct Counting<O> {
+    inner: O,
+    queries: AtomicUsize,
+}
+
+impl<O> Counting<O> {
+    pub fn new(inner: O) -> Counting<O> {
+        Counting {
+            inner,
+            queries: AtomicUsize::new(0),
+        }
+    }
+
+    /// Number of queries made so far, including any the oracle failed.
+    pub fn queries(&self) -> usize {
+        self.queries.load(Ordering::Relaxed)
+    }
+
+    /// Start counting from `0` again.
+    pub fn reset(&self) {
+        self.queries.store(0, Ordering::Relaxed);
+    }
+
+    pub fn inner(&self) -> &O {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> O {
+        self.inner
+    }
+
+    fn query<R>(&self, query: impl FnOnce(&O) -> R) -> R {
+        self.queries.fetch_add(1, Ordering::Relaxed);
+        query(&self.inner)
+    }
+}
+
+impl<O: EncryptionOracle> EncryptionOracle for Counting<O> {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, CrackError> {
+        self.query(|o| o.encrypt(plaintext))
+    }
+}
+
+impl<O: DecryptionOracle> DecryptionOracle for Counting<O> {
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, CrackError> {
+        self.query(|o| o.decrypt(ciphertext))
+    }
+}
+
+impl<O: ValidityOracle> ValidityOracle for Counting<O> {
+    fn is_valid(&self, ciphertext: &[u8]) -> Result<bool, CrackError> {
+        self.query(|o| o.is_valid(ciphertext))
+    }
+}
+
+/// Refuses queries to an oracle once a budget of queries is spent,
+/// with [CrackError::BudgetExhausted].
+#[derive(Debug)]
+pub struct Budget<O> {
+    inner: O,
+    budget: usize,
+    used: AtomicUsize,
+}
+
+impl<O> Budget<O> {
+    /// Allow `budget` queries to `inner`.
+    pub fn new(inner: O, budget: usize) -> Budget<O> {
+        Budget {
+            inner,
+            budget,
+            used: AtomicUsize::new(0),
+        }
+    }
+
+    /// Number of queries allowed so far.
+    pub fn used(&self) -> usize {
+        self.used.load(Ordering::Relaxed)
+    }
+
+    /// Number of queries that will still be allowed.
+    pub fn remaining(&self) -> usize {
+        self.budget - self.used()
+    }
+
+    pub fn inner(&self) -> &O {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> O {
+        self.inner
+    }
+
+    fn query<R>(&self, query: impl FnOnce(&O) -> Result<R, CrackError>) -> Result<R, CrackError> {
+        self.used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                (used < self.budget).then_some(used + 1)
+            })
+            .map_err(|_| CrackError::BudgetExhausted {
+                budget: self.budget,
+            })?;
+        query(&self.inner)
+    }
+}
+
+impl<O: EncryptionOracle> EncryptionOracle for Budget<O> {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, CrackError> {
+        self.query(|o| o.encrypt(plaintext))
+    }
+}
+
+impl<O: DecryptionOracle> DecryptionOracle for Budget<O> {
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, CrackError> {
+        self.query(|o| o.decrypt(ciphertext))
+    }
+}
+
+impl<O: ValidityOracle> ValidityOracle for Budget<O> {
+    fn is_valid(&self, ciphertext: &[u8]) -> Result<bool, CrackError> {
+        self.query(|o| o.is_valid(ciphertext))
+    }
+}
+
+/// What an oracle answered to a query.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Response {
+    /// The ciphertext of an [EncryptionOracle].
+    Ciphertext(Vec<u8>),
+
+    /// The plaintext of a [DecryptionOracle].
+    Plaintext(Vec<u8>),
+
+    /// The answer of a [ValidityOracle].
+    Valid(bool),
+
+    /// The oracle failed the query.
+    Failed(CrackError),
+}
+
+/// A query made to an oracle, and the oracle's response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Query {
+    input: Vec<u8>,
+    response: Response,
+}
+
+impl Query {
+    /// The plaintext or ciphertext the oracle was queried with.
+    pub fn get_input(&self) -> &[u8] {
+        &self.input
+    }
+
+    pub fn get_response(&self) -> &Response {
+        &self.response
+    }
+}
+
+/// Logs every query made to an oracle, along with its response.
+#[derive(Debug)]
+pub struct Transcript<O> {
+    inner: O,
+    log: Mutex<Vec<Query>>,
+}
+
+impl<O> Transcript<O> {
+    pub fn new(inner: O) -> Transcript<O> {
+        Transcript {
+            inner,
+            log: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// The queries made so far, in the order they were answered.
+    pub fn queries(&self) -> Vec<Query> {
+        self.log.lock().unwrap().clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.log.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn inner(&self) -> &O {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> O {
+        self.inner
+    }
+
+    fn query<R: Clone>(
+        &self,
+        input: &[u8],
+        query: impl FnOnce(&O) -> Result<R, CrackError>,
+        response: impl FnOnce(R) -> Response,
+    ) -> Result<R, CrackError> {
+        let res = query(&self.inner);
+        let response = match &res {
+            Ok(r) => response(r.clone()),
+            Err(e) => Response::Failed(*e),
+        };
+        self.log.lock().unwrap().push(Query {
+            input: input.to_vec(),
+            response,
+        });
+        res
+    }
+}
+
+impl<O: EncryptionOracle> EncryptionOracle for Transcript<O> {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, CrackError> {
+        self.query(plaintext, |o| o.encrypt(plaintext), Response::Ciphertext)
+    }
+}
+
+impl<O: DecryptionOracle> DecryptionOracle for Transcript<O> {
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, CrackError> {
+        self.query(ciphertext, |o| o.decrypt(ciphertext), Response::Plaintext)
+    }
+}
+
+impl<O: ValidityOracle> ValidityOracle for Transcript<O> {
+    fn is_valid(&self, ciphertext: &[u8]) -> Result<bool, CrackError> {
+        self.query(ciphertext, |o| o.is_valid(ciphertext), Response::Valid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// XORs every byte with `0x2a`, in both directions, and accepts even lengths only.
+    struct Toy;
+
+    impl EncryptionOracle for Toy {
+        fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, CrackError> {
+            Ok(plaintext.iter().map(|b| b ^ 0x2a).collect())
+        }
+    }
+
+    impl DecryptionOracle for Toy {
+        fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, CrackError> {
+            self.encrypt(ciphertext)
+        }
+    }
+
+    impl ValidityOracle for Toy {
+        fn is_valid(&self, ciphertext: &[u8]) -> Result<bool, CrackError> {
+            Ok(ciphertext.len().is_multiple_of(2))
+        }
+    }
+
+    fn factory() -> Toy {
+        Toy {}
+    }
+
+    #[test]
+    fn test_counting() {
+        let oracle = Counting::new(factory());
+        oracle.encrypt(b"a").unwrap();
+        oracle.decrypt(b"b").unwrap();
+        oracle.is_valid(b"c").unwrap();
+        assert_eq!(oracle.queries(), 3);
+
+        oracle.reset();
+        assert_eq!(oracle.queries(), 0);
+        assert_eq!(oracle.encrypt(&[0x2a]), Ok(vec![0]));
+        assert_eq!(oracle.queries(), 1);
+    }
+
+    #[test]
+    fn test_budget() {
+        let oracle = Budget::new(factory(), 2);
+        assert_eq!(oracle.is_valid(b"ab"), Ok(true));
+        assert_eq!(oracle.remaining(), 1);
+        assert_eq!(oracle.encrypt(b""), Ok(vec![]));
+        assert_eq!(
+            oracle.decrypt(b"x"),
+            Err(CrackError::BudgetExhausted { budget: 2 })
+        );
+        assert_eq!(oracle.used(), 2);
+        assert_eq!(oracle.remaining(), 0);
+    }
+
+    #[test]
+    fn test_transcript() {
+        let oracle = Transcript::new(Budget::new(factory(), 3));
+        oracle.encrypt(b"a").unwrap();
+        oracle.is_valid(b"abc").unwrap();
+        oracle.decrypt(&[0x2b]).unwrap();
+        oracle.decrypt(b"x").unwrap_err();
+
+        let queries = oracle.queries();
+        assert_eq!(oracle.len(), 4);
+        assert_eq!(queries[0].get_input(), b"a");
+        assert_eq!(
+            queries[0].get_response(),
+            &Response::Ciphertext(vec![b'a' ^ 0x2a])
+        );
+        assert_eq!(queries[1].get_response(), &Response::Valid(false));
+        assert_eq!(queries[2].get_response(), &Response::Plaintext(vec![1]));
+        assert_eq!(
+            queries[3].get_response(),
+            &Response::Failed(CrackError::BudgetExhausted { budget: 3 })
+        );
+    }
+
+    /// Wrappers stack, and share one oracle across worker threads.
+    #[test]
+    fn test_stacked_in_pool() {
+        let oracle = Counting::new(Transcript::new(factory()));
+        let jobs = (0..100u8).collect::<Vec<u8>>();
+        let res = crate::crack::pool::WorkerPool::new(4).map(&jobs, |j| oracle.encrypt(&[*j]));
+
+        assert!(res.iter().all(|r| r.is_ok()));
+        assert_eq!(oracle.queries(), 100);
+        assert_eq!(oracle.inner().len(), 100);
+    }
+}
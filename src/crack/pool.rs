@@ -0,0 +1,218 @@
+//! A bounded pool of worker threads for brute force jobs.
+//!
+//! Spawning a thread per candidate key thrashes the scheduler
+//! once there are many ciphertexts to attack.
+//! A [WorkerPool] runs a batch of jobs on a fixed number of scoped threads instead,
+//! which take the next job as soon as they finish the last one.
+
+use crate::crack::error::CrackError;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+/// Shared flag that asks running jobs to stop.
+///
+/// Clones share the same flag, so a clone can be handed to another thread to cancel from.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    /// Ask the jobs watching this token to stop.
+    /// Jobs that have already started run to completion.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Runs batches of jobs on a bounded number of worker threads.
+///
+/// Threads are scoped to each batch, so jobs may borrow from the caller.
+/// A pool with a single worker runs jobs on the calling thread,
+/// which suits jobs that are themselves run from a worker of another pool.
+#[derive(Copy, Clone, Debug)]
+pub struct WorkerPool {
+    workers: usize,
+}
+
+impl Default for WorkerPool {
+    /// A pool sized to the available parallelism of this machine.
+    fn default() -> WorkerPool {
+        WorkerPool::new(thread::available_parallelism().map_or(1, |n| n.get()))
+    }
+}
+
+impl WorkerPool {
+    /// A pool of `workers` threads. A pool always has at least one worker.
+    pub fn new(workers: usize) -> WorkerPool {
+        WorkerPool {
+            workers: workers.max(1),
+        }
+    }
+
+    pub fn workers(&self) -> usize {
+        self.workers
+    }
+
+    /// Run `job` over every item of `jobs`, returning the results in the order of `jobs`.
+    pub fn map<J, R, F>(&self, jobs: &[J], job: F) -> Vec<R>
+    where
+        J: Sync,
+        R: Send,
+        F: Fn(&J) -> R + Sync,
+    {
+        self.map_with(jobs, job, &CancelToken::new(), |_, _| {})
+            .expect("a batch without a CancelToken to cancel it can't be cancelled")
+    }
+
+    /// Like [WorkerPool::map], with cancellation and progress reporting.
+    ///
+    /// `progress` is called from the worker threads with the number of jobs completed so far
+    /// and the total number of jobs, every time a job completes.
+    /// Once `cancel` is cancelled, no more jobs are started,
+    /// and [CrackError::Cancelled] is returned unless every job had already completed.
+    pub fn map_with<J, R, F, P>(
+        &self,
+        jobs: &[J],
+        job: F,
+        cancel: &CancelToken,
+        progress: P,
+    ) -> Result<Vec<R>, CrackError>
+    where
+        J: Sync,
+        R: Send,
+        F: Fn(&J) -> R + Sync,
+        P: Fn(usize, usize) + Sync,
+    {
+        let next = AtomicUsize::new(0);
+        let completed = AtomicUsize::new(0);
+        let work = || {
+            let mut res: Vec<(usize, R)> = Vec::new();
+            while !cancel.is_cancelled() {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = jobs.get(i) else {
+                    break;
+                };
+                res.push((i, job(item)));
+                progress(completed.fetch_add(1, Ordering::Relaxed) + 1, jobs.len());
+            }
+            res
+        };
+
+        let workers = self.workers.min(jobs.len());
+        let mut results = if workers <= 1 {
+            work()
+        } else {
+            thread::scope(|s| {
+                let handles = (0..workers).map(|_| s.spawn(work)).collect::<Vec<_>>();
+                handles
+                    .into_iter()
+                    .flat_map(|h| h.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+                    .collect::<Vec<(usize, R)>>()
+            })
+        };
+
+        if results.len() < jobs.len() {
+            return Err(CrackError::Cancelled);
+        }
+        results.sort_by_key(|(i, _)| *i);
+        Ok(results.into_iter().map(|(_, r)| r).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+
+    fn factory() -> WorkerPool {
+        WorkerPool::new(4)
+    }
+
+    #[test]
+    fn test_map() {
+        let jobs = (0..1000u32).collect::<Vec<u32>>();
+        let expected = jobs.iter().map(|j| j * 2).collect::<Vec<u32>>();
+
+        for pool in [
+            factory(),
+            WorkerPool::new(1),
+            WorkerPool::new(0),
+            WorkerPool::default(),
+        ] {
+            assert_eq!(pool.map(&jobs, |j| j * 2), expected);
+        }
+        assert_eq!(WorkerPool::new(0).workers(), 1);
+        assert!(factory().map(&[] as &[u32], |j| *j).is_empty());
+    }
+
+    #[test]
+    fn test_bounded_workers() {
+        let threads = Mutex::new(HashSet::new());
+        factory().map(&[(); 100], |_| {
+            threads.lock().unwrap().insert(thread::current().id());
+        });
+
+        assert!(threads.into_inner().unwrap().len() <= 4);
+    }
+
+    #[test]
+    fn test_progress() {
+        let reported = Mutex::new(Vec::new());
+        let res = factory().map_with(
+            &[1, 2, 3, 4, 5],
+            |j| j + 1,
+            &CancelToken::new(),
+            |done, total| reported.lock().unwrap().push((done, total)),
+        );
+
+        assert_eq!(res, Ok(vec![2, 3, 4, 5, 6]));
+        let mut reported = reported.into_inner().unwrap();
+        reported.sort();
+        assert_eq!(reported, (1..=5).map(|done| (done, 5)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_cancel() {
+        let cancel = CancelToken::new();
+        let started = AtomicUsize::new(0);
+        let res = factory().map_with(
+            &[(); 1000],
+            |_| {
+                if started.fetch_add(1, Ordering::Relaxed) == 10 {
+                    cancel.clone().cancel();
+                }
+            },
+            &cancel,
+            |_, _| {},
+        );
+
+        assert_eq!(res, Err(CrackError::Cancelled));
+        assert!(cancel.is_cancelled());
+        assert!(started.load(Ordering::Relaxed) < 1000);
+
+        // Cancelling after every job completed doesn't discard the results.
+        let cancel = CancelToken::new();
+        let res = WorkerPool::new(1).map_with(
+            &[1, 2],
+            |j| *j,
+            &cancel,
+            |done, total| {
+                if done == total {
+                    cancel.cancel()
+                }
+            },
+        );
+        assert_eq!(res, Ok(vec![1, 2]));
+    }
+}
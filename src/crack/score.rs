@@ -0,0 +1,329 @@
+//! Plausibility scoring for candidate plaintexts.
+//!
+//! Breakers like [crate::crack::xor::brute] try many keys,
+//! and keep the plaintext that a [Scorer] rates the most plausible.
+//! Swap the [Scorer] to match the kind of plaintext that is expected.
+
+use std::collections::HashMap;
+
+/// Rates how plausible `data` is as plaintext.
+///
+/// Higher scores are more plausible.
+/// Scores are only meaningful relative to other scores from the same [Scorer].
+pub trait Scorer {
+    fn score(&self, data: &[u8]) -> f64;
+
+    /// Rate `data` on a scale that doesn't depend on its length,
+    /// so that plaintexts of different lengths can be compared.
+    ///
+    /// Defaults to [Scorer::score] divided by the length of `data`,
+    /// for scorers whose scores add up over every byte.
+    /// Scorers that already average over `data` return [Scorer::score] as is.
+    fn score_per_byte(&self, data: &[u8]) -> f64 {
+        if data.is_empty() {
+            return self.score(data);
+        }
+        self.score(data) / data.len() as f64
+    }
+}
+
+/// Relative frequencies of `a` to `z` in English text.
+const ENGLISH_LETTER_FREQUENCIES: [f64; 26] = [
+    0.08167, 0.01492, 0.02782, 0.04253, 0.12702, 0.02228, 0.02015, 0.06094, 0.06966, 0.00153,
+    0.00772, 0.04025, 0.02406, 0.06749, 0.07507, 0.01929, 0.00095, 0.05987, 0.06327, 0.09056,
+    0.02758, 0.00978, 0.02360, 0.00150, 0.01974, 0.00074,
+];
+
+/// Share of spaces in English text.
+const SPACE_FREQUENCY: f64 = 0.18;
+
+/// Share of other printable characters in English text: digits, punctuation, line breaks.
+const OTHER_PRINTABLE_FREQUENCY: f64 = 0.04;
+
+/// Share of unprintable bytes in English text, which should be close to none.
+const UNPRINTABLE_FREQUENCY: f64 = 0.0001;
+
+/// The heuristic of [crate::crack::xor::ascii_score],
+/// which rates letters and spaces above digits and punctuation.
+///
+/// Cheap, but it rates every letter the same.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct AsciiScorer;
+
+impl Scorer for AsciiScorer {
+    fn score(&self, data: &[u8]) -> f64 {
+        crate::crack::xor::ascii_score(data.to_vec()) as f64
+    }
+}
+
+/// Pearson's chi-squared test of `data` against the character frequencies of English text.
+///
+/// Letters are counted case-insensitively, alongside spaces, other printable characters
+/// and unprintable bytes. The score is the negated test statistic,
+/// so that text that is closer to English scores higher.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ChiSquaredScorer;
+
+impl ChiSquaredScorer {
+    /// Index of the frequency category `c` falls in:
+    /// 0 to 25 for letters, then space, other printable characters, and unprintable bytes.
+    fn category(c: u8) -> usize {
+        match c {
+            b'a'..=b'z' => (c - b'a') as usize,
+            b'A'..=b'Z' => (c - b'A') as usize,
+            b' ' => 26,
+            b'\t' | b'\n' | b'\r' | 33..=126 => 27,
+            _ => 28,
+        }
+    }
+}
+
+impl Scorer for ChiSquaredScorer {
+    fn score(&self, data: &[u8]) -> f64 {
+        let mut observed = [0usize; 29];
+        for c in data {
+            observed[ChiSquaredScorer::category(*c)] += 1;
+        }
+
+        let letters = 1.0 - SPACE_FREQUENCY - OTHER_PRINTABLE_FREQUENCY - UNPRINTABLE_FREQUENCY;
+        let expected = ENGLISH_LETTER_FREQUENCIES
+            .iter()
+            .map(|f| f * letters)
+            .chain([
+                SPACE_FREQUENCY,
+                OTHER_PRINTABLE_FREQUENCY,
+                UNPRINTABLE_FREQUENCY,
+            ])
+            .map(|f| f * data.len() as f64);
+
+        -observed
+            .iter()
+            .zip(expected)
+            .filter(|(_, e)| *e > 0.0)
+            .map(|(o, e)| (*o as f64 - e).powi(2) / e)
+            .sum::<f64>()
+    }
+}
+
+/// Percentage frequencies of the most common bigrams in English text.
+const ENGLISH_BIGRAMS: [(&str, f64); 50] = [
+    ("th", 3.56),
+    ("he", 3.07),
+    ("in", 2.43),
+    ("er", 2.05),
+    ("an", 1.99),
+    ("re", 1.85),
+    ("on", 1.76),
+    ("at", 1.49),
+    ("en", 1.45),
+    ("nd", 1.35),
+    ("ti", 1.34),
+    ("es", 1.34),
+    ("or", 1.28),
+    ("te", 1.20),
+    ("of", 1.17),
+    ("ed", 1.17),
+    ("is", 1.13),
+    ("it", 1.12),
+    ("al", 1.09),
+    ("ar", 1.07),
+    ("st", 1.05),
+    ("to", 1.04),
+    ("nt", 1.04),
+    ("ng", 0.95),
+    ("se", 0.93),
+    ("ha", 0.93),
+    ("as", 0.87),
+    ("ou", 0.87),
+    ("io", 0.83),
+    ("le", 0.83),
+    ("ve", 0.83),
+    ("co", 0.79),
+    ("me", 0.79),
+    ("de", 0.76),
+    ("hi", 0.76),
+    ("ri", 0.73),
+    ("ro", 0.73),
+    ("ic", 0.70),
+    ("ne", 0.69),
+    ("ea", 0.69),
+    ("ra", 0.69),
+    ("ce", 0.65),
+    ("li", 0.62),
+    ("ch", 0.60),
+    ("ll", 0.58),
+    ("be", 0.58),
+    ("ma", 0.57),
+    ("si", 0.55),
+    ("om", 0.55),
+    ("ur", 0.54),
+];
+
+/// Percentage frequencies of the most common trigrams in English text.
+const ENGLISH_TRIGRAMS: [(&str, f64); 30] = [
+    ("the", 1.81),
+    ("and", 0.73),
+    ("ing", 0.72),
+    ("ent", 0.42),
+    ("ion", 0.42),
+    ("her", 0.36),
+    ("for", 0.34),
+    ("tha", 0.33),
+    ("nth", 0.33),
+    ("int", 0.32),
+    ("ere", 0.31),
+    ("tio", 0.31),
+    ("ter", 0.30),
+    ("est", 0.28),
+    ("ers", 0.28),
+    ("ati", 0.26),
+    ("hat", 0.26),
+    ("ate", 0.25),
+    ("all", 0.25),
+    ("eth", 0.24),
+    ("hes", 0.24),
+    ("ver", 0.24),
+    ("his", 0.24),
+    ("oft", 0.22),
+    ("ith", 0.21),
+    ("fth", 0.21),
+    ("sth", 0.21),
+    ("oth", 0.21),
+    ("res", 0.21),
+    ("ont", 0.20),
+];
+
+/// Average log-likelihood of the letter n-grams in `data`, against a table of n-gram frequencies.
+///
+/// N-grams are taken case-insensitively from within runs of letters.
+/// N-grams missing from the table count as `floor` percent.
+/// Whitespace and common punctuation are neutral,
+/// while every other byte counts as an n-gram at `floor` percent.
+#[derive(Clone, Debug)]
+pub struct NGramScorer {
+    n: usize,
+    log_probabilities: HashMap<Vec<u8>, f64>,
+    log_floor: f64,
+}
+
+impl NGramScorer {
+    /// Scorer for n-grams of length `n`, given their percentage frequencies in `table`.
+    ///
+    /// Entries in `table` that aren't `n` letters long are ignored.
+    pub fn new(n: usize, table: &[(&str, f64)], floor: f64) -> NGramScorer {
+        let log_probabilities = table
+            .iter()
+            .filter(|(ngram, _)| ngram.len() == n && ngram.bytes().all(|c| c.is_ascii_alphabetic()))
+            .map(|(ngram, percentage)| {
+                (ngram.to_ascii_lowercase().into_bytes(), percentage.log10())
+            })
+            .collect();
+        NGramScorer {
+            n,
+            log_probabilities,
+            log_floor: floor.log10(),
+        }
+    }
+
+    /// Scorer for the most common bigrams in English text.
+    pub fn english_bigrams() -> NGramScorer {
+        NGramScorer::new(2, &ENGLISH_BIGRAMS, 0.01)
+    }
+
+    /// Scorer for the most common trigrams in English text.
+    pub fn english_trigrams() -> NGramScorer {
+        NGramScorer::new(3, &ENGLISH_TRIGRAMS, 0.001)
+    }
+
+    fn is_neutral(c: u8) -> bool {
+        c.is_ascii_whitespace() || b".,;:!?'\"-".contains(&c)
+    }
+}
+
+impl Scorer for NGramScorer {
+    fn score(&self, data: &[u8]) -> f64 {
+        let mut total = 0.0;
+        let mut count = 0;
+        for word in data.split(|c| !c.is_ascii_alphabetic()) {
+            for ngram in word.windows(self.n) {
+                total += self
+                    .log_probabilities
+                    .get(&ngram.to_ascii_lowercase())
+                    .copied()
+                    .unwrap_or(self.log_floor);
+                count += 1;
+            }
+        }
+        let penalised = data
+            .iter()
+            .filter(|c| !c.is_ascii_alphabetic() && !NGramScorer::is_neutral(**c))
+            .count();
+        total += penalised as f64 * self.log_floor;
+        count += penalised;
+
+        if count == 0 {
+            return self.log_floor;
+        }
+        total / count as f64
+    }
+
+    fn score_per_byte(&self, data: &[u8]) -> f64 {
+        self.score(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::hex::Hexadecimal;
+    use crate::crack::xor::brute;
+
+    fn factory() -> ChiSquaredScorer {
+        ChiSquaredScorer {}
+    }
+
+    /// Ranking common letters above rare ones is what [AsciiScorer] can't do.
+    #[test]
+    fn test_letter_frequencies() {
+        let common = "etaoin shrdlu".as_bytes();
+        let rare = "zqxjkv bgfpwm".as_bytes();
+
+        assert_eq!(AsciiScorer {}.score(common), AsciiScorer {}.score(rare));
+        assert!(factory().score(common) > factory().score(rare));
+        assert!(factory().score(b"plain english") > factory().score(b"pl\x00in\xffenglish"));
+    }
+
+    #[test]
+    fn test_ngrams() {
+        let english = "the cat sat on the mat".as_bytes();
+        let shuffled = "hte atc tsa no hte tma".as_bytes();
+
+        for scorer in [
+            NGramScorer::english_bigrams(),
+            NGramScorer::english_trigrams(),
+        ] {
+            assert!(scorer.score(english) > scorer.score(shuffled));
+            assert!(scorer.score(english) > scorer.score(b"the\x01cat\x02sat"));
+            assert_eq!(scorer.score(b""), scorer.log_floor);
+        }
+    }
+
+    #[test]
+    fn test_brute_with_scorers() {
+        let crypt_text = "1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736";
+        let scorers: [&(dyn Scorer + Sync); 4] = [
+            &AsciiScorer {},
+            &factory(),
+            &NGramScorer::english_bigrams(),
+            &NGramScorer::english_trigrams(),
+        ];
+
+        for scorer in scorers {
+            let res = brute(&Hexadecimal {}, crypt_text, scorer);
+            assert_eq!(
+                res.get_decrypted_result(),
+                "Cooking MC's like a pound of bacon".as_bytes()
+            );
+        }
+    }
+}
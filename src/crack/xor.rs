@@ -1,7 +1,12 @@
-use crate::codec::adapter::Codec;
-use crate::crack::DecryptResult;
-use std::sync::Arc;
-use std::thread;
+use crate::codec::adapter::{Codec, CodecAPI};
+use crate::codec::error::CodecError;
+use crate::codec::raw::Raw;
+use crate::codec::wrap::{LineEnding, Wrapped};
+use crate::crack::error::CrackError;
+use crate::crack::pool::WorkerPool;
+use crate::crack::score::Scorer;
+use crate::crack::{hamming_distance, DecryptResult};
+use std::ops::RangeInclusive;
 
 // Read a series of characters and assign a score for each.
 // The higher the score, the more ASCII characters are in the input data,
@@ -10,7 +15,7 @@ pub fn ascii_score(score_me: Vec<u8>) -> usize {
     score_me
         .iter()
         .map(|&c| {
-            let res_c = match c {
+            match c {
                 65..=90 => 20,  // ASCII Uppercase alphabet
                 97..=122 => 20, // ASCII Lowercase alphabet
                 48..=57 => 10,  // ASCII Digit
@@ -20,176 +25,327 @@ pub fn ascii_score(score_me: Vec<u8>) -> usize {
                 91..=96 => 1,   // More ASCII punctuation
                 123..=126 => 1, // More ASCII punctuation
                 _ => 0,         // disregard non-legible characters
-            };
-            res_c
+            }
         })
         .fold(0, |x, acc| x + acc as usize)
 }
 
 /// Perform a brute force attack on ``crypt_text`` using
 /// a single byte cipher, operating in the encoding format specified by `codec`.
-pub fn brute<T>(codec: &T, crypt_text: &str) -> DecryptResult
+///
+/// `crypt_text` may be a `&str` for textual encodings,
+/// or a byte slice of binary content for [crate::codec::raw::Raw].
+/// Each candidate plaintext is rated with `scorer`, and the highest rated one wins.
+pub fn brute<T, D, S>(codec: &T, crypt_text: &D, scorer: &S) -> DecryptResult
 where
-    T: Codec + Copy + std::marker::Sync + std::marker::Send + 'static,
+    T: Codec,
+    D: AsRef<[u8]> + ?Sized,
+    S: Scorer + Sync + ?Sized,
 {
-    let mut leader = DecryptResult {
-        key: vec![0],
-        score: 0,
-        decrypted_result: vec![0],
-    };
-    let codec: Arc<T> = Arc::new(*codec);
-    let brute_cipher_max: u8 = 255;
-
-    let mut queue: Vec<thread::JoinHandle<DecryptResult>> = Vec::new();
-    for i in 0..=brute_cipher_max {
-        let codec: Arc<T> = Arc::clone(&codec);
-        queue.push(async_brute_sub(
-            &codec,
-            crypt_text.as_bytes().to_vec(),
-            vec![i],
-        ))
-    }
+    brute_top_n(codec, crypt_text, scorer, 1)
+        .pop()
+        .expect("there is always a candidate for every key byte")
+}
 
-    for q in queue {
-        if let Ok(res) = q.join() {
-            if leader.score < res.score {
-                leader = res
-            }
-        }
-    }
+/// Like [crate::crack::xor::brute], but returns the `n` highest rated candidates,
+/// best score first, instead of only the winner.
+///
+/// Candidates with the same score are ordered by key byte.
+/// Use [crate::crack::confidence_margin] to tell how clearly the winner won.
+pub fn brute_top_n<T, D, S>(codec: &T, crypt_text: &D, scorer: &S, n: usize) -> Vec<DecryptResult>
+where
+    T: Codec,
+    D: AsRef<[u8]> + ?Sized,
+    S: Scorer + Sync + ?Sized,
+{
+    brute_top_n_in(&WorkerPool::default(), codec, crypt_text, scorer, n)
+}
 
-    leader
+/// Like [crate::crack::xor::brute_top_n], but tries the candidate keys on the workers of `pool`.
+///
+/// When attacking many ciphertexts at once, run them on one pool,
+/// and attack each one with a single worker `WorkerPool::new(1)`,
+/// so that the number of threads stays bounded.
+pub fn brute_top_n_in<T, D, S>(
+    pool: &WorkerPool,
+    codec: &T,
+    crypt_text: &D,
+    scorer: &S,
+    n: usize,
+) -> Vec<DecryptResult>
+where
+    T: Codec,
+    D: AsRef<[u8]> + ?Sized,
+    S: Scorer + Sync + ?Sized,
+{
+    let crypt_text = decode_content(codec, crypt_text.as_ref());
+    brute_decoded_in(pool, codec, &crypt_text, scorer, n)
 }
 
-/// Provide an async helper function for [crate::crack::xor::brute]
-/// to run [crate::crack::xor::xor_decrypt]
-/// asynchronously.
-fn async_brute_sub<'a, 'b, T>(
-    codec: &Arc<T>,
-    crypt_text: Vec<u8>,
-    cipher: Vec<u8>,
-) -> thread::JoinHandle<DecryptResult>
+/// Shared tail of [crate::crack::xor::brute_top_n_in] and [crate::crack::xor::try_brute],
+/// for `crypt_text` that has already been decoded with `codec`.
+///
+/// `codec` is only used to encode the key of each candidate.
+fn brute_decoded_in<T, S>(
+    pool: &WorkerPool,
+    codec: &T,
+    crypt_text: &[u8],
+    scorer: &S,
+    n: usize,
+) -> Vec<DecryptResult>
 where
-    T: Codec + std::marker::Sync + std::marker::Send + 'static,
+    T: Codec,
+    S: Scorer + Sync + ?Sized,
 {
-    let codec = Arc::clone(codec);
-    thread::spawn(move || {
-        let cipher_hex = codec.encode(cipher.as_slice());
-        let decrypt_res = codec.to_plain(
-            xor_decrypt(codec.as_ref(), crypt_text.as_slice(), cipher_hex.as_slice()).as_slice(),
-        );
-        let current_ascii_score = DecryptResult {
-            key: cipher_hex,
-            score: ascii_score(decrypt_res.clone()),
-            decrypted_result: decrypt_res,
-        };
-
-        current_ascii_score
-    })
+    let ciphers = (0..=u8::MAX).collect::<Vec<u8>>();
+    let candidates = pool.map(&ciphers, |cipher| brute_sub(crypt_text, *cipher, scorer));
+    top_candidates(codec, candidates, n)
 }
 
-/// Decrypt byte-slice of content with a given key, using repeated key xor.
-/// Returns an encoded vector of bytes.
-pub fn xor_decrypt<T: Codec>(codec: &T, content: &[u8], key: &[u8]) -> Vec<u8> {
-    let key = codec.to_plain(key);
-    let keyslice = key.as_slice();
-
-    // Decrypt step 1: Split at newlines in content, make iterable to operate on
-    let content_string = content.iter().map(|c| *c as char).collect::<String>();
-    let content_string_lines = content_string.lines();
-
-    // Decrypt step 2: Run XOR at byte level for each line
-    let mut outer_res: Vec<Vec<u8>> = Vec::new();
-    for line in content_string_lines {
-        // Use `codec` to decode this line
-        let decoded_line = codec.to_plain(line.as_bytes());
-
-        let res = decoded_line
-            .chunks(keyslice.len())
-            .flat_map(|chunk| {
-                chunk
-                    .iter()
-                    .zip(keyslice)
-                    .map(|(l, h)| l ^ h)
-                    .collect::<Vec<u8>>()
-            })
-            .collect::<Vec<u8>>();
-        // Push result to `outer_res`.
-        outer_res.push(codec.encode(&res))
+/// Like [crate::crack::xor::brute_decoded_in], but tries every key on the calling thread,
+/// for callers that already spread their work over a [WorkerPool].
+pub(crate) fn brute_decoded<T, S>(
+    codec: &T,
+    crypt_text: &[u8],
+    scorer: &S,
+    n: usize,
+) -> Vec<DecryptResult>
+where
+    T: Codec,
+    S: Scorer + ?Sized,
+{
+    let candidates = (0..=u8::MAX)
+        .map(|cipher| brute_sub(crypt_text, cipher, scorer))
+        .collect();
+    top_candidates(codec, candidates, n)
+}
+
+/// Keep the `n` best of the `(cipher, score, decrypted_result)` `candidates`, best score first.
+fn top_candidates<T: Codec>(
+    codec: &T,
+    mut candidates: Vec<(u8, f64, Vec<u8>)>,
+    n: usize,
+) -> Vec<DecryptResult> {
+    // Stable, so candidates with the same score stay in key byte order.
+    candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    candidates
+        .into_iter()
+        .take(n)
+        .map(|(cipher, score, decrypted_result)| DecryptResult {
+            key: codec.encode(&[cipher]),
+            plain_key: vec![cipher],
+            score,
+            decrypted_result,
+        })
+        .collect()
+}
+
+/// Fallible counterpart to [crate::crack::xor::brute].
+///
+/// Rejects `crypt_text` with a [CodecError] if it is not valid in the encoding format
+/// specified by `codec`, instead of brute forcing whatever bytes survive decoding.
+pub fn try_brute<T, D, S>(
+    codec: &T,
+    crypt_text: &D,
+    scorer: &S,
+) -> Result<DecryptResult, CodecError>
+where
+    T: Codec,
+    D: AsRef<[u8]> + ?Sized,
+    S: Scorer + Sync + ?Sized,
+{
+    let crypt_text = try_decode_content(codec, crypt_text.as_ref())?;
+    Ok(
+        brute_decoded_in(&WorkerPool::default(), codec, &crypt_text, scorer, 1)
+            .pop()
+            .expect("there is always a candidate for every key byte"),
+    )
+}
+
+/// Helper for [crate::crack::xor::brute], run as a job on a [WorkerPool],
+/// that XORs plain `crypt_text` with a single byte `cipher` and scores the result.
+fn brute_sub<S>(crypt_text: &[u8], cipher: u8, scorer: &S) -> (u8, f64, Vec<u8>)
+where
+    S: Scorer + ?Sized,
+{
+    let decrypt_res = repeating_key_xor(crypt_text, &[cipher]);
+    (cipher, scorer.score(&decrypt_res), decrypt_res)
+}
+
+/// XOR two byte slices of equal length together.
+///
+/// Returns [CrackError::LengthMismatch] if `a` and `b` have different lengths.
+pub fn fixed_xor(a: &[u8], b: &[u8]) -> Result<Vec<u8>, CrackError> {
+    if a.len() != b.len() {
+        return Err(CrackError::LengthMismatch {
+            left: a.len(),
+            right: b.len(),
+        });
     }
+    Ok(a.iter().zip(b).map(|(l, r)| l ^ r).collect())
+}
 
-    // Create buffer for joined output
-    let mut joined: Vec<u8> = Vec::new();
+/// XOR `data` with `key`, repeating `key` for as long as `data` goes on.
+///
+/// An empty `key` leaves `data` unchanged.
+pub fn repeating_key_xor(data: &[u8], key: &[u8]) -> Vec<u8> {
+    let mut res = data.to_vec();
+    xor_into(&mut res, key);
+    res
+}
 
-    // Convoluted logic for adding newlines `\n` if
-    // that line is not the last 'line' of the resulting decrypted text.
-    let mut it = outer_res.iter().peekable();
-    while it.peek().is_some() {
-        if let Some(inner_res) = it.next() {
-            for res in inner_res {
-                joined.push(*res);
-            }
-            if it.peek().is_some() {
-                joined.push(b'\\');
-                joined.push(b'n');
-            }
-        }
+/// In-place counterpart to [crate::crack::xor::repeating_key_xor].
+pub fn xor_into(data: &mut [u8], key: &[u8]) {
+    for (c, k) in data.iter_mut().zip(key.iter().cycle()) {
+        *c ^= k;
     }
-    joined
 }
 
-/// XOR encrypts ASCII byte-slice `content`
-/// with an encoded byte slice `key`.
-pub fn xor_encrypt<T: Codec>(codec: &T, content: &[u8], key: &[u8]) -> Vec<u8> {
-    // Convert content to string and then split it into lines
-    let content = content.iter().map(|&c| c as char).collect::<String>();
-    let content_lines = content.lines();
-
-    // encode, because we need to iterate over the correct chunk size
-    // assume `key` is already encoded
-    let encoded_content_lines = content_lines.map(|line| codec.encode(line.as_bytes()));
-
-    let mut encrypted_lines: Vec<Vec<u8>> = Vec::new();
-    // Must process in chunk sizes that match the byte-slice size of the key.
-    for encoded in encoded_content_lines {
-        let this_line = encoded
-            .chunks(key.len())
-            .flat_map(|chunk| {
-                let decoded_chunk = codec.to_plain(chunk);
-                let inner_key = codec.to_plain(key);
-                decoded_chunk
-                    .iter()
-                    .zip(inner_key)
-                    .map(|(l, h)| l ^ h)
-                    .collect::<Vec<u8>>()
-            })
-            .collect::<Vec<u8>>();
+/// Decode `content` with `codec` as a whole.
+///
+/// Line breaks and other whitespace in textual content are formatting, not content,
+/// so they are skipped as [Wrapped] skips them.
+fn decode_content<T: Codec>(codec: &T, content: &[u8]) -> Vec<u8> {
+    if !codec.is_textual() {
+        return codec.to_plain(content);
+    }
+    Wrapped::new(codec, 0, LineEnding::Lf).to_plain(content)
+}
 
-        encrypted_lines.push(this_line);
+/// Strict counterpart to `decode_content`, which rejects malformed content.
+///
+/// Offsets in errors are positions in `content`, whitespace included.
+pub(crate) fn try_decode_content<T: Codec>(
+    codec: &T,
+    content: &[u8],
+) -> Result<Vec<u8>, CodecError> {
+    if !codec.is_textual() {
+        return codec.try_to_plain(content);
     }
+    Wrapped::new(codec, 0, LineEnding::Lf).try_to_plain(content)
+}
+
+/// Rank the keysizes in `keysizes` by how likely it is
+/// that plain `crypt_text` was XORed with a repeating key of that size,
+/// and return the `top_n` most likely ones.
+///
+/// Bytes of English text are closer to each other in Hamming distance than random bytes,
+/// and XORing two blocks with the same key doesn't change the distance between them.
+/// So the right keysize gives the lowest Hamming distance between consecutive blocks,
+/// normalized by the keysize and averaged over every pair of blocks.
+/// Keysizes that don't fit at least two blocks in `crypt_text` are skipped.
+pub fn estimate_keysizes(
+    crypt_text: &[u8],
+    keysizes: RangeInclusive<usize>,
+    top_n: usize,
+) -> Vec<usize> {
+    let mut distances = keysizes
+        .filter(|keysize| *keysize > 0 && crypt_text.len() >= keysize * 2)
+        .map(|keysize| {
+            let blocks = crypt_text.chunks_exact(keysize).collect::<Vec<&[u8]>>();
+            let pairs = blocks.windows(2);
+            let count = pairs.len();
+            let total = pairs
+                .map(|pair| hamming_distance(pair[0], pair[1]))
+                .sum::<usize>();
+            (keysize, total as f64 / (count * keysize) as f64)
+        })
+        .collect::<Vec<(usize, f64)>>();
+    distances.sort_by(|a, b| a.1.total_cmp(&b.1));
+    distances
+        .into_iter()
+        .take(top_n)
+        .map(|(keysize, _)| keysize)
+        .collect()
+}
 
-    let mut joined: Vec<u8> = Vec::new();
-    let mut it = encrypted_lines.iter().peekable();
-    while it.peek().is_some() {
-        while let Some(inner_res) = it.next() {
-            for res in inner_res {
-                joined.push(*res);
+/// Break repeating-key XOR on `crypt_text`, encoded with `codec`.
+///
+/// Tries the `top_n` most likely keysizes in `keysizes`, as ranked by [estimate_keysizes].
+/// For each keysize, the ciphertext is transposed into one column per key byte,
+/// and each column is solved as single-byte XOR with [crate::crack::xor::brute].
+/// `scorer` rates both the columns and the whole plaintext for each keysize.
+///
+/// Returns one result per keysize tried, best score first.
+/// As with [crate::crack::xor::brute], the key is encoded with `codec`.
+/// Returns a [CodecError] if `crypt_text` is not valid in the encoding format of `codec`.
+pub fn break_repeating_key_xor<T, D, S>(
+    codec: &T,
+    crypt_text: &D,
+    keysizes: RangeInclusive<usize>,
+    top_n: usize,
+    scorer: &S,
+) -> Result<Vec<DecryptResult>, CodecError>
+where
+    T: Codec,
+    D: AsRef<[u8]> + ?Sized,
+    S: Scorer + Sync + ?Sized,
+{
+    let crypt_text = try_decode_content(codec, crypt_text.as_ref())?;
+
+    let mut results = estimate_keysizes(&crypt_text, keysizes, top_n)
+        .into_iter()
+        .map(|keysize| {
+            let columns = (0..keysize)
+                .map(|column| {
+                    crypt_text
+                        .iter()
+                        .skip(column)
+                        .step_by(keysize)
+                        .copied()
+                        .collect::<Vec<u8>>()
+                })
+                .collect::<Vec<Vec<u8>>>();
+            // Solve columns in parallel, each on a single worker.
+            let key = WorkerPool::default().map(&columns, |column| {
+                brute_top_n_in(&WorkerPool::new(1), &Raw {}, column.as_slice(), scorer, 1)[0]
+                    .plain_key[0]
+            });
+            let decrypted_result = repeating_key_xor(&crypt_text, &key);
+            DecryptResult {
+                key: codec.encode(&key),
+                score: scorer.score(&decrypted_result),
+                plain_key: key,
+                decrypted_result,
             }
-        }
-        if it.peek().is_some() {
-            joined.push(b'0');
-            joined.push(b'a');
-        }
-    }
+        })
+        .collect::<Vec<DecryptResult>>();
+
+    // Multiples of the right keysize decrypt just as well, so prefer the shortest key.
+    results.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(a.key.len().cmp(&b.key.len()))
+    });
+    Ok(results)
+}
 
-    joined
+/// Decrypt byte-slice of content with a given key, using repeated key xor.
+/// Returns an encoded vector of bytes.
+///
+/// `content` and `key` are both encoded with `codec`.
+/// `content` may be wrapped over several lines.
+pub fn xor_decrypt<T: Codec>(codec: &T, content: &[u8], key: &[u8]) -> Vec<u8> {
+    let key = codec.to_plain(key);
+    codec.encode(&repeating_key_xor(&decode_content(codec, content), &key))
+}
+
+/// XOR encrypts plain byte-slice `content`
+/// with an encoded byte slice `key`.
+/// Returns the plain, unencoded ciphertext.
+pub fn xor_encrypt<T: Codec>(codec: &T, content: &[u8], key: &[u8]) -> Vec<u8> {
+    repeating_key_xor(content, &codec.to_plain(key))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::codec::adapter::CodecAPI;
+    use crate::codec::b64::Base64;
     use crate::codec::hex::Hexadecimal;
+    use crate::codec::wrap::Wrapped;
+    use crate::crack::score::{AsciiScorer, ChiSquaredScorer};
 
     fn factory() -> Hexadecimal {
         Hexadecimal {}
@@ -210,6 +366,7 @@ mod tests {
                 .map(|c| *c as char)
                 .collect::<String>()
                 .as_str(),
+            &AsciiScorer {},
         );
 
         assert_eq!(
@@ -221,6 +378,174 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_try_brute() {
+        let res = try_brute(
+            &factory(),
+            "1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736",
+            &AsciiScorer {},
+        )
+        .unwrap();
+        assert_eq!(
+            factory().to_plain_string(&res.get_key()),
+            factory().to_plain_string("58".as_bytes())
+        );
+
+        assert_eq!(
+            try_brute(
+                &factory(),
+                "1b37373331363f78151b7f2b78343g",
+                &AsciiScorer {}
+            )
+            .unwrap_err(),
+            CodecError::InvalidCharacter {
+                offset: 29,
+                byte: b'g'
+            }
+        );
+    }
+
+    #[test]
+    fn test_try_brute_trailing_newline() {
+        // Same as `brute`, line breaks are formatting and not malformed content.
+        let res = try_brute(
+            &factory(),
+            "1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736\n",
+            &AsciiScorer {},
+        )
+        .unwrap();
+        assert_eq!(res.get_plain_key(), vec![0x58]);
+
+        assert_eq!(
+            try_brute(&factory(), "1b37\n33g1\n", &AsciiScorer {}).unwrap_err(),
+            CodecError::InvalidCharacter {
+                offset: 7,
+                byte: b'g'
+            }
+        );
+    }
+
+    #[test]
+    fn test_fixed_xor() {
+        let a = factory().to_plain("1c0111001f010100061a024b53535009181c".as_bytes());
+        let b = factory().to_plain("686974207468652062756c6c277320657965".as_bytes());
+
+        assert_eq!(
+            fixed_xor(&a, &b).map(|res| factory().encode_to_string(&res)),
+            Ok("746865206b696420646f6e277420706c6179".to_string())
+        );
+        assert_eq!(
+            fixed_xor(&a, &b[1..]),
+            Err(CrackError::LengthMismatch {
+                left: 18,
+                right: 17
+            })
+        );
+    }
+
+    /// Newlines in plaintext are XORed like any other byte.
+    #[test]
+    fn test_repeating_key_xor() {
+        let res = repeating_key_xor(REPEATEDXOR_UNENCRYPTED.as_bytes(), b"ICE");
+        assert_eq!(factory().encode_to_string(&res), REPEATEDXOR_ENCRYPTED);
+
+        let mut in_place = res.clone();
+        xor_into(&mut in_place, b"ICE");
+        assert_eq!(in_place, REPEATEDXOR_UNENCRYPTED.as_bytes());
+
+        assert_eq!(repeating_key_xor(b"\n\n", b""), b"\n\n");
+    }
+
+    const BREAK_ME: &str = "It is a truth universally acknowledged, that a single man in possession \
+        of a good fortune, must be in want of a wife. However little known the feelings or views of \
+        such a man may be on his first entering a neighbourhood, this truth is so well fixed in the \
+        minds of the surrounding families, that he is considered the rightful property of some one \
+        or other of their daughters. My dear Mr. Bennet, said his lady to him one day, have you \
+        heard that Netherfield Park is let at last? Mr. Bennet replied that he had not. But it is, \
+        returned she; for Mrs. Long has just been here, and she told me all about it.\n\
+        Mr. Bennet made no answer. Do you not want to know who has taken it? cried his wife \
+        impatiently. You want to tell me, and I have no objection to hearing it.";
+
+    #[test]
+    fn test_estimate_keysizes() {
+        let crypt_text = repeating_key_xor(BREAK_ME.as_bytes(), b"Terminator");
+        let keysizes = estimate_keysizes(&crypt_text, 2..=40, 3);
+
+        assert_eq!(keysizes.len(), 3);
+        assert!(keysizes.contains(&10));
+        assert!(estimate_keysizes(b"tiny", 3..=40, 3).is_empty());
+    }
+
+    #[test]
+    fn test_break_repeating_key_xor() {
+        let codec = Base64 {};
+        let crypt_text =
+            Wrapped::mime(codec).encode(&repeating_key_xor(BREAK_ME.as_bytes(), b"Terminator"));
+
+        let res =
+            break_repeating_key_xor(&codec, &crypt_text, 2..=40, 3, &ChiSquaredScorer {}).unwrap();
+
+        assert_eq!(res.len(), 3);
+        assert_eq!(codec.to_plain(&res[0].get_key()), b"Terminator");
+        assert_eq!(res[0].get_decrypted_result(), BREAK_ME.as_bytes());
+
+        assert_eq!(
+            break_repeating_key_xor(&codec, "SGVsbG8-", 2..=40, 3, &AsciiScorer {}).unwrap_err(),
+            CodecError::InvalidCharacter {
+                offset: 7,
+                byte: b'-'
+            }
+        );
+    }
+
+    #[test]
+    fn test_brute_top_n() {
+        let crypt_text = "1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736";
+        let res = brute_top_n(&factory(), crypt_text, &ChiSquaredScorer {}, 5);
+
+        assert_eq!(res.len(), 5);
+        assert!(res.windows(2).all(|w| w[0].get_score() >= w[1].get_score()));
+        assert_eq!(res[0].get_key_byte(), Some(0x58));
+        assert_eq!(res[0].get_key(), "58".as_bytes());
+        assert_eq!(
+            res[0].get_decrypted_result(),
+            "Cooking MC's like a pound of bacon".as_bytes()
+        );
+        assert!(crate::crack::confidence_margin(&res).unwrap() > 0.0);
+
+        let all = brute_top_n(&factory(), crypt_text, &ChiSquaredScorer {}, 1000);
+        assert_eq!(all.len(), 256);
+        assert_eq!(all[0].get_score(), res[0].get_score());
+        assert!(brute_top_n(&factory(), crypt_text, &ChiSquaredScorer {}, 0).is_empty());
+    }
+
+    /// Binary ciphertext containing `\n` and bytes above 0x7f, through [Raw].
+    #[test]
+    fn test_raw_xor() {
+        let plain = "Line one\nLine two\r\nLine three é".as_bytes();
+        // 'L' ^ 0x46 == b'\n'
+        let key = &[0x46, 0x80];
+        let crypt_text = xor_encrypt(&Raw {}, plain, key);
+
+        assert!(crypt_text.contains(&b'\n') && crypt_text.iter().any(|c| *c > 0x7f));
+        assert_eq!(crypt_text.len(), plain.len());
+        assert_eq!(xor_decrypt(&Raw {}, &crypt_text, key), plain);
+    }
+
+    #[test]
+    fn test_raw_brute() {
+        let plain =
+            "Cooking MC's like a pound of bacon\nQuick to the point, to the point, no faking";
+        // 'e' ^ 0x6f == b'\n'
+        let crypt_text = plain.bytes().map(|c| c ^ 0x6f).collect::<Vec<u8>>();
+        assert!(crypt_text.contains(&b'\n'));
+
+        let res = brute(&Raw {}, crypt_text.as_slice(), &ChiSquaredScorer {});
+
+        assert_eq!(res.get_key(), vec![0x6f]);
+        assert_eq!(res.get_decrypted_result(), plain.as_bytes());
+    }
+
     #[test]
     fn test_xor_decrypt() {
         let cases = [
@@ -274,9 +599,9 @@ a282b2f20430a652e2c652a3124333a653e2b2027630c692b20283165286326302e27282f"
             .as_bytes();
         let expected = "Burning 'em, if you ain't quick and nimble
 I go crazy when I hear a cymbal";
-        let key = "58".as_bytes();
+        let key = "ICE".as_bytes();
         let codec = factory();
-        let res = xor_decrypt(&codec, input, key);
+        let res = xor_decrypt(&codec, input, codec.encode(key).as_slice());
 
         assert_eq!(codec.to_plain_string(res.as_slice()), expected,)
     }
@@ -286,8 +611,8 @@ I go crazy when I hear a cymbal";
     fn test_multiline_xor_encrypt() {
         let input = "Burning 'em, if you ain't quick and nimble
 I go crazy when I hear a cymbal";
-        let expected = "0b3637272a2b2e63622c2e69692a23693a2a3c6324202d623d63343c2a26226324272765272
-a282b2f20430a652e2c652a3124333a653e2b2027630c692b20283165286326302e27282f";
+        let expected = "1a2d2a3631363f787f3d357478313e7821372d783931367f2c78292d313b337839363c78\
+                        3631353a343d5211783f37783b2a392221782f303d36781178303d392a7839783b21353a3934";
         let key = "58".as_bytes();
         let codec = factory();
         let res = xor_encrypt(&codec, input.as_bytes(), key);
@@ -296,9 +621,8 @@ a282b2f20430a652e2c652a3124333a653e2b2027630c692b20283165286326302e27282f";
 
     const REPEATEDXOR_UNENCRYPTED: &str = "Burning 'em, if you ain't quick and nimble
 I go crazy when I hear a cymbal";
-    const REPEATEDXOR_ENCRYPTED: &str =
-        "0b3637272a2b2e63622c2e69692a23693a2a3c6324202d623d63343c2a26226324272765272
-a282b2f20430a652e2c652a3124333a653e2b2027630c692b20283165286326302e27282f";
+    const REPEATEDXOR_ENCRYPTED: &str = "0b3637272a2b2e63622c2e69692a23693a2a3c6324202d623d63343c2a26226324272765272\
+                                         a282b2f20430a652e2c652a3124333a653e2b2027630c692b20283165286326302e27282f";
 
     #[test]
     fn test_encrypt_repeated_xor() {
@@ -0,0 +1,48 @@
+//! Errors returned when parsing the formats in [crate::formats].
+
+use std::fmt;
+
+/// Describes why input could not be parsed.
+///
+/// Offsets are zero-based positions in the input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FormatError {
+    /// The pair starting at `offset` has no `=` between its key and value.
+    MissingSeparator { offset: usize },
+
+    /// The pair starting at `offset` has an empty key.
+    EmptyKey { offset: usize },
+
+    /// The key of the pair starting at `offset` is already in an earlier pair.
+    DuplicateKey { offset: usize },
+
+    /// The `%` at `offset` is not followed by two hex digits of an ASCII character.
+    InvalidEscape { offset: usize },
+
+    /// The input is not valid UTF-8 from `offset` onwards.
+    InvalidUtf8 { offset: usize },
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::MissingSeparator { offset } => {
+                write!(f, "missing '=' in the pair at offset {}", offset)
+            }
+            FormatError::EmptyKey { offset } => {
+                write!(f, "empty key in the pair at offset {}", offset)
+            }
+            FormatError::DuplicateKey { offset } => {
+                write!(f, "duplicate key in the pair at offset {}", offset)
+            }
+            FormatError::InvalidEscape { offset } => {
+                write!(f, "invalid escape at offset {}", offset)
+            }
+            FormatError::InvalidUtf8 { offset } => {
+                write!(f, "invalid UTF-8 at offset {}", offset)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
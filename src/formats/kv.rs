@@ -0,0 +1,232 @@
+//! `key=value` pairs separated by `&`, like `foo=bar&baz=qux`,
+//! as in <https://cryptopals.com/sets/2/challenges/13>.
+//!
+//! `&`, `=` and `%` in keys and values are percent-encoded as `%26`, `%3D` and `%25`,
+//! so user input can't add pairs of its own.
+//! Parsing is strict: every pair needs a non-empty key and an `=`,
+//! and a key may only appear once, so `role=user&role=admin` is rejected
+//! rather than resolved one way or the other.
+
+use super::error::FormatError;
+use std::fmt;
+use std::str::FromStr;
+
+/// Ordered `key=value` pairs with unique keys.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KvMap {
+    pairs: Vec<(String, String)>,
+}
+
+impl KvMap {
+    pub fn new() -> KvMap {
+        KvMap::default()
+    }
+
+    /// Parse `input`. Empty input is an empty map.
+    pub fn parse(input: &str) -> Result<KvMap, FormatError> {
+        let mut map = KvMap::new();
+        if input.is_empty() {
+            return Ok(map);
+        }
+        let mut offset = 0;
+        for pair in input.split('&') {
+            let Some((raw_key, raw_value)) = pair.split_once('=') else {
+                return Err(FormatError::MissingSeparator { offset });
+            };
+            if raw_key.is_empty() {
+                return Err(FormatError::EmptyKey { offset });
+            }
+            let key = unescape(raw_key, offset)?;
+            let value = unescape(raw_value, offset + raw_key.len() + 1)?;
+            if map.get(&key).is_some() {
+                return Err(FormatError::DuplicateKey { offset });
+            }
+            map.pairs.push((key, value));
+            offset += pair.len() + 1;
+        }
+        Ok(map)
+    }
+
+    /// Parse `input`, which must be UTF-8.
+    pub fn parse_bytes(input: &[u8]) -> Result<KvMap, FormatError> {
+        let input = std::str::from_utf8(input).map_err(|e| FormatError::InvalidUtf8 {
+            offset: e.valid_up_to(),
+        })?;
+        KvMap::parse(input)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Set `key` to `value`, keeping its position if it is already in the map,
+    /// or adding it at the end.
+    pub fn insert(&mut self, key: &str, value: &str) {
+        match self.pairs.iter_mut().find(|(k, _)| k == key) {
+            Some((_, v)) => *v = value.to_string(),
+            None => self.pairs.push((key.to_string(), value.to_string())),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// The pairs, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.pairs.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Serialize the pairs in order, escaping them.
+    pub fn encode(&self) -> String {
+        self.pairs
+            .iter()
+            .map(|(k, v)| format!("{}={}", escape(k), escape(v)))
+            .collect::<Vec<String>>()
+            .join("&")
+    }
+}
+
+impl fmt::Display for KvMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.encode())
+    }
+}
+
+impl FromStr for KvMap {
+    type Err = FormatError;
+
+    fn from_str(s: &str) -> Result<KvMap, FormatError> {
+        KvMap::parse(s)
+    }
+}
+
+/// The encoded profile of a user with `email`, which is `email=...&uid=10&role=user`.
+pub fn profile_for(email: &str) -> String {
+    let mut profile = KvMap::new();
+    profile.insert("email", email);
+    profile.insert("uid", "10");
+    profile.insert("role", "user");
+    profile.encode()
+}
+
+/// Percent-encode the characters that have a meaning in the format.
+fn escape(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => res.push_str("%26"),
+            '=' => res.push_str("%3D"),
+            '%' => res.push_str("%25"),
+            _ => res.push(c),
+        }
+    }
+    res
+}
+
+/// Decode the percent-encoding of `s`, which starts at `offset` in the input.
+fn unescape(s: &str, offset: usize) -> Result<String, FormatError> {
+    let mut res = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('%') {
+        res.push_str(&rest[..i]);
+        let at = offset + (s.len() - rest.len()) + i;
+        let c = rest
+            .get(i + 1..i + 3)
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            .filter(|c| c.is_ascii() && rest.as_bytes()[i + 1].is_ascii_hexdigit())
+            .ok_or(FormatError::InvalidEscape { offset: at })?;
+        res.push(c as char);
+        rest = &rest[i + 3..];
+    }
+    res.push_str(rest);
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn factory() -> KvMap {
+        KvMap::parse("foo=bar&baz=qux&zap=zazzle").unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let map = factory();
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get("baz"), Some("qux"));
+        assert_eq!(map.get("qux"), None);
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            vec![("foo", "bar"), ("baz", "qux"), ("zap", "zazzle")]
+        );
+        assert_eq!(map.encode(), "foo=bar&baz=qux&zap=zazzle");
+
+        assert!(KvMap::parse("").unwrap().is_empty());
+        assert_eq!(KvMap::parse("a=").unwrap().get("a"), Some(""));
+        assert_eq!(KvMap::parse("a=b=c").unwrap().get("a"), Some("b=c"));
+        assert_eq!(
+            "x=%26%3d%25".parse::<KvMap>().unwrap().get("x"),
+            Some("&=%")
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let cases = [
+            ("foo=bar&baz", FormatError::MissingSeparator { offset: 8 }),
+            ("foo=bar&", FormatError::MissingSeparator { offset: 8 }),
+            ("=bar", FormatError::EmptyKey { offset: 0 }),
+            (
+                "role=user&role=admin",
+                FormatError::DuplicateKey { offset: 10 },
+            ),
+            ("a=b&c=%2", FormatError::InvalidEscape { offset: 6 }),
+            ("a=%zz", FormatError::InvalidEscape { offset: 2 }),
+            ("a=%+1", FormatError::InvalidEscape { offset: 2 }),
+            ("a=%ff", FormatError::InvalidEscape { offset: 2 }),
+            ("k%2=v", FormatError::InvalidEscape { offset: 1 }),
+            ("k%3D=%zz", FormatError::InvalidEscape { offset: 5 }),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(KvMap::parse(input), Err(expected), "{:?}", input);
+        }
+        assert_eq!(
+            KvMap::parse_bytes(b"a=\xff"),
+            Err(FormatError::InvalidUtf8 { offset: 2 })
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut map = factory();
+        map.insert("foo", "a&b=c%d");
+        map.insert("new", "");
+        let encoded = map.to_string();
+
+        assert_eq!(encoded, "foo=a%26b%3Dc%25d&baz=qux&zap=zazzle&new=");
+        assert_eq!(KvMap::parse(&encoded), Ok(map));
+    }
+
+    /// <https://cryptopals.com/sets/2/challenges/13>
+    #[test]
+    fn test_profile_for() {
+        assert_eq!(
+            profile_for("foo@bar.com"),
+            "email=foo@bar.com&uid=10&role=user"
+        );
+
+        let profile = KvMap::parse(&profile_for("foo@bar.com&role=admin")).unwrap();
+        assert_eq!(profile.get("email"), Some("foo@bar.com&role=admin"));
+        assert_eq!(profile.get("role"), Some("user"));
+        assert_eq!(profile.len(), 3);
+    }
+}
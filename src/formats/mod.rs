@@ -0,0 +1,7 @@
+//! # Formats
+//!
+//! Structured plaintext formats that the oracles in [crate::crack::oracle] encrypt,
+//! so that attacks can forge content that parses.
+
+pub mod error;
+pub mod kv;
@@ -3,8 +3,8 @@
 //! Zed is working through <https://cryptopals.com/> to learn how to actually use rust.
 //!
 //! - Challenges are solved by writing and running tests in relevant modules.
-//! They're not solved or addressed directly.
-//! For example, you can't find a _crate::challenges::set1::challenge3_ module that directly addresses <https://cryptopals.com/sets/1/challenges/3>.
+//!   They're not solved or addressed directly.
+//!   For example, you can't find a _crate::challenges::set1::challenge3_ module that directly addresses <https://cryptopals.com/sets/1/challenges/3>.
 //! - That's because the Cryptopals challenges build upon each other to form a comprehensive cryptographic toolkit.
 //! - Except: _Some_ challenges that result in one-off code _are_ addressed direcctly in [crate::challenges].
 //!
@@ -16,9 +16,18 @@
 /// Implementation of various encryption and decryption tasks
 pub mod crack;
 
+/// Block ciphers for the attacks in [crate::crack] to attack.
+pub mod cipher;
+
 /// Encoding formats implemented to provide encode and decode utilities.
 pub mod codec;
 
+/// Structured plaintext formats, like `key=value` cookies.
+pub mod formats;
+
+/// Padding schemes for block cipher modes.
+pub mod padding;
+
 /// Shared utilities for this crate.
 pub mod utils;
 
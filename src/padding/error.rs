@@ -0,0 +1,53 @@
+//! Errors returned when removing padding with a [crate::padding::Padding].
+
+use std::fmt;
+
+/// Describes exactly why padding could not be removed.
+///
+/// Offsets are zero-based positions in the padded input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaddingError {
+    /// There is no content, so there can't be any padding either.
+    Empty,
+
+    /// Content of `len` bytes is not a whole number of blocks of `block_size` bytes.
+    UnalignedLength { len: usize, block_size: usize },
+
+    /// The final byte, which gives the padding length, is `0`.
+    ZeroPadByte,
+
+    /// The final byte gives a padding length of `byte`,
+    /// which is larger than a block of `block_size` bytes.
+    PadByteTooLarge { byte: u8, block_size: usize },
+
+    /// The padding byte at `offset` doesn't have the value the padding scheme requires.
+    InconsistentPadding { offset: usize },
+
+    /// The final block has no `0x80` marker where the padding should start.
+    MissingMarker,
+}
+
+impl fmt::Display for PaddingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaddingError::Empty => write!(f, "empty input has no padding"),
+            PaddingError::UnalignedLength { len, block_size } => write!(
+                f,
+                "length {} is not a multiple of the block size {}",
+                len, block_size
+            ),
+            PaddingError::ZeroPadByte => write!(f, "padding length byte is 0"),
+            PaddingError::PadByteTooLarge { byte, block_size } => write!(
+                f,
+                "padding length {} is larger than the block size {}",
+                byte, block_size
+            ),
+            PaddingError::InconsistentPadding { offset } => {
+                write!(f, "inconsistent padding byte at offset {}", offset)
+            }
+            PaddingError::MissingMarker => write!(f, "padding marker 0x80 not found"),
+        }
+    }
+}
+
+impl std::error::Error for PaddingError {}
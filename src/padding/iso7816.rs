@@ -0,0 +1,75 @@
+//! ISO/IEC 7816-4 padding.
+//!
+//! A `0x80` marker byte, followed by as many zero bytes as it takes to fill the block.
+//! Aligned content gets a whole block of padding.
+
+use super::error::PaddingError;
+use super::{check_blocks, padding_len, Padding};
+
+/// Pad `data` to a whole number of blocks of `block_size` bytes.
+///
+/// # Panics
+///
+/// If `block_size` is `0`.
+pub fn pad(data: &[u8], block_size: usize) -> Vec<u8> {
+    let n = padding_len(data.len(), block_size, usize::MAX);
+    let mut res = data.to_vec();
+    res.push(0x80);
+    res.resize(data.len() + n, 0);
+    res
+}
+
+/// Validate and remove ISO/IEC 7816-4 padding from `data`.
+///
+/// The marker must be in the final block.
+pub fn unpad(data: &[u8], block_size: usize) -> Result<&[u8], PaddingError> {
+    check_blocks(data, block_size)?;
+    let last_block = data.len() - block_size;
+    let Some(marker) = data[last_block..].iter().rposition(|b| *b != 0) else {
+        return Err(PaddingError::MissingMarker);
+    };
+    let offset = last_block + marker;
+    if data[offset] != 0x80 {
+        return Err(PaddingError::InconsistentPadding { offset });
+    }
+    Ok(&data[..offset])
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Iso7816;
+
+impl Padding for Iso7816 {
+    fn pad(&self, data: &[u8], block_size: usize) -> Vec<u8> {
+        pad(data, block_size)
+    }
+
+    fn unpad<'a>(&self, data: &'a [u8], block_size: usize) -> Result<&'a [u8], PaddingError> {
+        unpad(data, block_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pad_unpad() {
+        assert_eq!(pad(b"abcde", 8), b"abcde\x80\x00\x00");
+        assert_eq!(pad(b"abcdefg", 8), b"abcdefg\x80");
+        assert_eq!(pad(b"", 4), b"\x80\x00\x00\x00");
+        assert_eq!(unpad(b"abcde\x80\x00\x00", 8), Ok(&b"abcde"[..]));
+        assert_eq!(
+            unpad(b"ab\x80\x00\x80\x00\x00\x00", 8),
+            Ok(&b"ab\x80\x00"[..])
+        );
+
+        assert_eq!(
+            Iso7816 {}.unpad(b"abcde\x81\x00\x00", 8),
+            Err(PaddingError::InconsistentPadding { offset: 5 })
+        );
+        assert_eq!(
+            unpad(b"abcdefgh\x00\x00\x00\x00\x00\x00\x00\x00", 8),
+            Err(PaddingError::MissingMarker)
+        );
+    }
+}
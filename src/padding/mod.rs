@@ -0,0 +1,86 @@
+//! # Padding
+//!
+//! Padding schemes that fill content up to a whole number of blocks,
+//! for block cipher modes like ECB and CBC that only process whole blocks.
+//!
+//! Each scheme implements [crate::padding::Padding], and its module also has `pad` and `unpad` functions.
+//! Removing padding validates it strictly,
+//! and a [crate::padding::error::PaddingError] says exactly what was wrong with it,
+//! because that is what a padding oracle leaks.
+
+pub mod error;
+pub mod iso7816;
+pub mod pkcs7;
+pub mod x923;
+pub mod zero;
+
+use error::PaddingError;
+
+/// A padding scheme.
+pub trait Padding {
+    /// Pad `data` to a whole number of blocks of `block_size` bytes.
+    ///
+    /// # Panics
+    ///
+    /// If the scheme can't pad to `block_size`, e.g. a `block_size` of `0`.
+    fn pad(&self, data: &[u8], block_size: usize) -> Vec<u8>;
+
+    /// Validate and remove the padding from `data`,
+    /// which must be a whole number of blocks of `block_size` bytes.
+    fn unpad<'a>(&self, data: &'a [u8], block_size: usize) -> Result<&'a [u8], PaddingError>;
+}
+
+/// Check that `data` is a non-empty, whole number of blocks of `block_size` bytes,
+/// returning its final byte.
+fn check_blocks(data: &[u8], block_size: usize) -> Result<u8, PaddingError> {
+    let Some(last) = data.last() else {
+        return Err(PaddingError::Empty);
+    };
+    if block_size == 0 || !data.len().is_multiple_of(block_size) {
+        return Err(PaddingError::UnalignedLength {
+            len: data.len(),
+            block_size,
+        });
+    }
+    Ok(*last)
+}
+
+/// Check the length byte `n` that ends the padding of `data`,
+/// and that the `n - 1` bytes of padding before it are all `filler`.
+fn check_length_padding(
+    data: &[u8],
+    block_size: usize,
+    filler: Option<u8>,
+) -> Result<&[u8], PaddingError> {
+    let byte = check_blocks(data, block_size)?;
+    if byte == 0 {
+        return Err(PaddingError::ZeroPadByte);
+    }
+    if byte as usize > block_size {
+        return Err(PaddingError::PadByteTooLarge { byte, block_size });
+    }
+    let start = data.len() - byte as usize;
+    let filler = filler.unwrap_or(byte);
+    if let Some(i) = data[start..data.len() - 1]
+        .iter()
+        .position(|b| *b != filler)
+    {
+        return Err(PaddingError::InconsistentPadding { offset: start + i });
+    }
+    Ok(&data[..start])
+}
+
+/// Number of padding bytes that fill `len` bytes up to the next whole block,
+/// adding a whole block if `len` is already aligned.
+///
+/// # Panics
+///
+/// If `block_size` is `0`, or more than `max`.
+fn padding_len(len: usize, block_size: usize, max: usize) -> usize {
+    assert!(
+        (1..=max).contains(&block_size),
+        "block size must be between 1 and {}",
+        max
+    );
+    block_size - len % block_size
+}
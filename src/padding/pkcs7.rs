@@ -0,0 +1,117 @@
+//! PKCS#7 padding, [RFC 5652 §6.3](https://www.rfc-editor.org/rfc/rfc5652#section-6.3).
+//!
+//! `n` bytes of padding, each with the value `n`. Aligned content gets a whole block of padding.
+
+use super::error::PaddingError;
+use super::{check_length_padding, padding_len, Padding};
+
+/// Pad `data` to a whole number of blocks of `block_size` bytes.
+///
+/// # Panics
+///
+/// If `block_size` is `0` or more than `255`.
+pub fn pad(data: &[u8], block_size: usize) -> Vec<u8> {
+    let n = padding_len(data.len(), block_size, 255);
+    let mut res = data.to_vec();
+    res.resize(data.len() + n, n as u8);
+    res
+}
+
+/// Validate and remove PKCS#7 padding from `data`.
+pub fn unpad(data: &[u8], block_size: usize) -> Result<&[u8], PaddingError> {
+    check_length_padding(data, block_size, None)
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Pkcs7;
+
+impl Padding for Pkcs7 {
+    fn pad(&self, data: &[u8], block_size: usize) -> Vec<u8> {
+        pad(data, block_size)
+    }
+
+    fn unpad<'a>(&self, data: &'a [u8], block_size: usize) -> Result<&'a [u8], PaddingError> {
+        unpad(data, block_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// <https://cryptopals.com/sets/2/challenges/9>
+    #[test]
+    fn test_pad() {
+        assert_eq!(
+            pad(b"YELLOW SUBMARINE", 20),
+            b"YELLOW SUBMARINE\x04\x04\x04\x04"
+        );
+        assert_eq!(
+            pad(b"YELLOW SUBMARINE", 16),
+            [b"YELLOW SUBMARINE", &[16; 16][..]].concat()
+        );
+        assert_eq!(pad(b"", 8), [8; 8]);
+        assert_eq!(pad(b"abc", 1), b"abc\x01");
+        assert_eq!(pad(&[0; 300], 255).len(), 510);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_pad_invalid_block_size() {
+        pad(b"abc", 256);
+    }
+
+    /// <https://cryptopals.com/sets/2/challenges/15>
+    #[test]
+    fn test_unpad() {
+        assert_eq!(
+            unpad(b"ICE ICE BABY\x04\x04\x04\x04", 16),
+            Ok(&b"ICE ICE BABY"[..])
+        );
+        assert_eq!(
+            unpad(b"ICE ICE BABY\x05\x05\x05\x05", 16),
+            Err(PaddingError::InconsistentPadding { offset: 11 })
+        );
+        assert_eq!(
+            unpad(b"ICE ICE BABY\x01\x02\x03\x04", 16),
+            Err(PaddingError::InconsistentPadding { offset: 12 })
+        );
+        assert_eq!(unpad(&[16; 16], 16), Ok(&b""[..]));
+
+        for len in [0, 1, 15, 17, 40] {
+            let data = (0..len as u8).collect::<Vec<u8>>();
+            assert_eq!(unpad(&pad(&data, 16), 16), Ok(data.as_slice()));
+        }
+    }
+
+    #[test]
+    fn test_unpad_errors() {
+        assert_eq!(unpad(b"", 16), Err(PaddingError::Empty));
+        assert_eq!(
+            unpad(&[1; 15], 16),
+            Err(PaddingError::UnalignedLength {
+                len: 15,
+                block_size: 16
+            })
+        );
+        assert_eq!(
+            unpad(&[1; 16], 0),
+            Err(PaddingError::UnalignedLength {
+                len: 16,
+                block_size: 0
+            })
+        );
+        assert_eq!(unpad(b"abcdefg\x00", 8), Err(PaddingError::ZeroPadByte));
+        assert_eq!(
+            unpad(b"abcdefg\x09", 8),
+            Err(PaddingError::PadByteTooLarge {
+                byte: 9,
+                block_size: 8
+            })
+        );
+        assert_eq!(
+            Pkcs7 {}.unpad(b"abcdef\x03\x02", 8),
+            Err(PaddingError::InconsistentPadding { offset: 6 })
+        );
+    }
+}
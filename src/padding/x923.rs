@@ -0,0 +1,58 @@
+//! ANSI X9.23 padding.
+//!
+//! `n - 1` zero bytes, followed by a byte with the value `n`.
+//! Aligned content gets a whole block of padding.
+
+use super::error::PaddingError;
+use super::{check_length_padding, padding_len, Padding};
+
+/// Pad `data` to a whole number of blocks of `block_size` bytes.
+///
+/// # Panics
+///
+/// If `block_size` is `0` or more than `255`.
+pub fn pad(data: &[u8], block_size: usize) -> Vec<u8> {
+    let n = padding_len(data.len(), block_size, 255);
+    let mut res = data.to_vec();
+    res.resize(data.len() + n - 1, 0);
+    res.push(n as u8);
+    res
+}
+
+/// Validate and remove ANSI X9.23 padding from `data`.
+pub fn unpad(data: &[u8], block_size: usize) -> Result<&[u8], PaddingError> {
+    check_length_padding(data, block_size, Some(0))
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct AnsiX923;
+
+impl Padding for AnsiX923 {
+    fn pad(&self, data: &[u8], block_size: usize) -> Vec<u8> {
+        pad(data, block_size)
+    }
+
+    fn unpad<'a>(&self, data: &'a [u8], block_size: usize) -> Result<&'a [u8], PaddingError> {
+        unpad(data, block_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pad_unpad() {
+        assert_eq!(pad(b"abcde", 8), b"abcde\x00\x00\x03");
+        assert_eq!(pad(b"abcdefgh", 4), b"abcdefgh\x00\x00\x00\x04");
+        assert_eq!(unpad(b"abcde\x00\x00\x03", 8), Ok(&b"abcde"[..]));
+        assert_eq!(
+            AnsiX923 {}.unpad(b"abcde\x00\x01\x03", 8),
+            Err(PaddingError::InconsistentPadding { offset: 6 })
+        );
+        assert_eq!(
+            unpad(b"abcde\x00\x00\x00", 8),
+            Err(PaddingError::ZeroPadByte)
+        );
+    }
+}
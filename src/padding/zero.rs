@@ -0,0 +1,58 @@
+//! Zero padding.
+//!
+//! As many zero bytes as it takes to fill the last block, and none for aligned content.
+//! Removing it removes every trailing zero byte, so content that ends in zeros doesn't
+//! survive the round trip. Only use it where the content can't end in zeros.
+
+use super::error::PaddingError;
+use super::{check_blocks, Padding};
+
+/// Pad `data` to a whole number of blocks of `block_size` bytes.
+///
+/// Empty content is padded to a block of zeros, so that it is a whole block too.
+///
+/// # Panics
+///
+/// If `block_size` is `0`.
+pub fn pad(data: &[u8], block_size: usize) -> Vec<u8> {
+    assert!(block_size > 0, "block size must be at least 1");
+    let len = data.len().div_ceil(block_size).max(1) * block_size;
+    let mut res = data.to_vec();
+    res.resize(len, 0);
+    res
+}
+
+/// Remove zero padding from `data`.
+pub fn unpad(data: &[u8], block_size: usize) -> Result<&[u8], PaddingError> {
+    check_blocks(data, block_size)?;
+    let end = data.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+    Ok(&data[..end])
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ZeroPadding;
+
+impl Padding for ZeroPadding {
+    fn pad(&self, data: &[u8], block_size: usize) -> Vec<u8> {
+        pad(data, block_size)
+    }
+
+    fn unpad<'a>(&self, data: &'a [u8], block_size: usize) -> Result<&'a [u8], PaddingError> {
+        unpad(data, block_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pad_unpad() {
+        assert_eq!(pad(b"abcde", 8), b"abcde\x00\x00\x00");
+        assert_eq!(pad(b"abcdefgh", 8), b"abcdefgh");
+        assert_eq!(pad(b"", 4), [0; 4]);
+        assert_eq!(unpad(b"abcde\x00\x00\x00", 8), Ok(&b"abcde"[..]));
+        assert_eq!(ZeroPadding {}.unpad(&[0; 4], 4), Ok(&b""[..]));
+        assert_eq!(unpad(b"", 4), Err(PaddingError::Empty));
+    }
+}
@@ -39,3 +39,19 @@ where
         })
         .collect()
 }
+
+/// Read the whole contents of a file as bytes, without splitting it into lines.
+pub fn read_bytes<P>(filename: P) -> io::Result<Vec<u8>>
+where
+    P: AsRef<Path>,
+{
+    std::fs::read(filename)
+}
+
+/// Write `data` to a file, replacing the file if it already exists.
+pub fn write_bytes<P>(filename: P, data: &[u8]) -> io::Result<()>
+where
+    P: AsRef<Path>,
+{
+    std::fs::write(filename, data)
+}
@@ -1,3 +1,4 @@
 //! Shared utilities
 
 pub mod fs;
+pub mod rand;
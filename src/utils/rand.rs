@@ -0,0 +1,114 @@
+//! A small pseudo-random number generator for keys and coin flips in challenge oracles.
+//!
+//! It is SplitMix64, seeded from the random keys the standard library gives each [RandomState],
+//! so the crate needs no dependencies. It is not cryptographically secure,
+//! see `IvSource` in [crate::crack::oracle::challenges].
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::ops::RangeInclusive;
+
+/// A SplitMix64 generator.
+///
+/// Seed it with [Rng::from_seed] for reproducible runs.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Default for Rng {
+    /// A generator seeded differently every time.
+    fn default() -> Rng {
+        Rng::from_seed(RandomState::new().build_hasher().finish())
+    }
+}
+
+impl Rng {
+    /// A generator seeded differently every time.
+    pub fn new() -> Rng {
+        Rng::default()
+    }
+
+    /// A generator that always produces the same output for the same `seed`.
+    pub fn from_seed(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Fill `buf` with random bytes.
+    pub fn fill(&mut self, buf: &mut [u8]) {
+        for chunk in buf.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    /// `len` random bytes.
+    pub fn bytes(&mut self, len: usize) -> Vec<u8> {
+        let mut res = vec![0; len];
+        self.fill(&mut res);
+        res
+    }
+
+    /// A random number in `range`, which must not be empty.
+    ///
+    /// # Panics
+    ///
+    /// If `range` is empty.
+    pub fn range(&mut self, range: RangeInclusive<usize>) -> usize {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "range must not be empty");
+        let span = (end - start) as u128 + 1;
+        // Multiply and keep the high half, which is close enough to uniform for small spans.
+        start + ((self.next_u64() as u128 * span) >> 64) as usize
+    }
+
+    /// `true` or `false`, with equal probability.
+    pub fn coin(&mut self) -> bool {
+        self.next_u64() >> 63 == 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn factory() -> Rng {
+        Rng::from_seed(1234567)
+    }
+
+    #[test]
+    fn test_seeded() {
+        // First output of SplitMix64 seeded with 1234567.
+        assert_eq!(factory().next_u64(), 6457827717110365317);
+        assert_eq!(factory().bytes(20), factory().bytes(20));
+        assert_ne!(factory().bytes(16), Rng::from_seed(7654321).bytes(16));
+        assert_ne!(Rng::new().bytes(16), Rng::new().bytes(16));
+    }
+
+    #[test]
+    fn test_distribution() {
+        let mut rng = factory();
+        let mut counts = [0; 6];
+        let mut heads = 0;
+        for _ in 0..6000 {
+            counts[rng.range(5..=10) - 5] += 1;
+            heads += rng.coin() as usize;
+        }
+        assert!(
+            counts.iter().all(|c| (800..1200).contains(c)),
+            "{:?}",
+            counts
+        );
+        assert!((2700..3300).contains(&heads));
+        assert_eq!(rng.range(3..=3), 3);
+        assert_eq!(rng.bytes(13).len(), 13);
+    }
+}